@@ -4,6 +4,8 @@ use std::{
     hash::Hash,
 };
 
+use crate::grid::{Grid, Point};
+
 pub trait Element: Copy + Clone + Hash + Eq {}
 impl<T: Copy + Clone + Hash + Eq> Element for T {}
 
@@ -27,12 +29,10 @@ impl<T: Element, const N: usize> BucketQueue<T, N> {
 
             inner[v].insert(t);
         }
-        let new = BucketQueue {
-            inner: inner,
+        BucketQueue {
+            inner,
             priorities: init,
-        };
-
-        new
+        }
     }
 
     pub fn modify_key(&mut self, item: T, to: usize) {
@@ -51,11 +51,33 @@ impl<T: Element, const N: usize> BucketQueue<T, N> {
     }
 
     pub fn decrease_key(&mut self, item: T, by: usize) {
-        let Some(&current_priority) = self.priorities.get(&item) else { return };
+        let Some(&current_priority) = self.priorities.get(&item) else {
+            return;
+        };
         let to = current_priority.saturating_sub(by);
         self.move_item(item, to, current_priority);
     }
 
+    /// Insert an item, or move it if it is already queued
+    pub fn push(&mut self, item: T, priority: usize) {
+        assert!(priority < N);
+        match self.priorities.get(&item) {
+            Some(&from) => self.move_item(item, priority, from),
+            None => {
+                self.inner[priority].insert(item);
+                self.priorities.insert(item, priority);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.priorities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.priorities.is_empty()
+    }
+
     pub fn pop_min(&mut self) -> Option<Node<T>> {
         for (p, bucket) in self.inner.iter_mut().enumerate() {
             if let Some(x) = bucket.items.iter().next().copied() {
@@ -71,6 +93,389 @@ impl<T: Element, const N: usize> BucketQueue<T, N> {
     }
 }
 
+/// A bucket queue for monotone priorities, as used by Dial's algorithm.
+///
+/// Buckets grow on demand, and the queue remembers the lowest possibly non-empty
+/// bucket so popping never rescans buckets below the last popped priority.
+/// Priorities must never be set below that of the last popped item.
+pub struct MonotoneBucketQueue<T: Element> {
+    inner: Vec<Bucket<T>>,
+    priorities: HashMap<T, usize>,
+    cursor: usize,
+}
+
+impl<T: Element> Default for MonotoneBucketQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element> MonotoneBucketQueue<T> {
+    pub fn new() -> MonotoneBucketQueue<T> {
+        MonotoneBucketQueue {
+            inner: Vec::new(),
+            priorities: HashMap::new(),
+            cursor: 0,
+        }
+    }
+
+    /// Insert an item, or move it if it is already queued
+    pub fn push(&mut self, item: T, priority: usize) {
+        assert!(
+            priority >= self.cursor,
+            "priority {priority} is below the current minimum {}",
+            self.cursor
+        );
+        if priority >= self.inner.len() {
+            self.inner.resize_with(priority + 1, Bucket::create);
+        }
+        if let Some(from) = self.priorities.insert(item, priority) {
+            self.inner[from].remove(item);
+        }
+        self.inner[priority].insert(item);
+    }
+
+    pub fn modify_key(&mut self, item: T, to: usize) {
+        if self.priorities.contains_key(&item) {
+            self.push(item, to);
+        }
+    }
+
+    pub fn decrease_key(&mut self, item: T, by: usize) {
+        let Some(&current_priority) = self.priorities.get(&item) else {
+            return;
+        };
+        let to = current_priority.saturating_sub(by).max(self.cursor);
+        self.push(item, to);
+    }
+
+    pub fn priority(&self, item: T) -> Option<usize> {
+        self.priorities.get(&item).copied()
+    }
+
+    pub fn peek_min(&self) -> Option<Node<T>> {
+        if self.is_empty() {
+            return None;
+        }
+        self.inner[self.cursor..]
+            .iter()
+            .zip(self.cursor..)
+            .find_map(|(bucket, p)| {
+                bucket.items.iter().next().map(|&x| Node {
+                    value: x,
+                    priority: p,
+                })
+            })
+    }
+
+    pub fn pop_min(&mut self) -> Option<Node<T>> {
+        if self.is_empty() {
+            return None;
+        }
+        while self.inner[self.cursor].items.is_empty() {
+            self.cursor += 1;
+        }
+        let bucket = &mut self.inner[self.cursor];
+        let x = *bucket.items.iter().next()?;
+        bucket.remove(x);
+        self.priorities.remove(&x);
+        Some(Node {
+            value: x,
+            priority: self.cursor,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.priorities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.priorities.is_empty()
+    }
+}
+
+const NONE: usize = usize::MAX;
+
+/// A bucket queue over keys with a dense `key -> usize` index, avoiding all hashing.
+///
+/// Each bucket is an intrusive doubly linked list threaded through per-index vectors,
+/// so moving an item between buckets is O(1). Items within a bucket pop in
+/// last-in first-out order, making the pop order deterministic.
+pub struct IndexedBucketQueue<T: Element, F: Fn(T) -> usize> {
+    index: F,
+    heads: Vec<usize>,
+    items: Vec<Option<T>>,
+    priorities: Vec<usize>,
+    prev: Vec<usize>,
+    next: Vec<usize>,
+    cursor: usize,
+    len: usize,
+}
+
+impl<T: Element, F: Fn(T) -> usize> IndexedBucketQueue<T, F> {
+    /// `index` must map every key to a distinct value below `capacity`
+    pub fn new(capacity: usize, index: F) -> Self {
+        IndexedBucketQueue {
+            index,
+            heads: Vec::new(),
+            items: vec![None; capacity],
+            priorities: vec![NONE; capacity],
+            prev: vec![NONE; capacity],
+            next: vec![NONE; capacity],
+            cursor: 0,
+            len: 0,
+        }
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let (prev, next) = (self.prev[i], self.next[i]);
+        if prev == NONE {
+            self.heads[self.priorities[i]] = next;
+        } else {
+            self.next[prev] = next;
+        }
+        if next != NONE {
+            self.prev[next] = prev;
+        }
+    }
+
+    fn link(&mut self, i: usize, priority: usize) {
+        if priority >= self.heads.len() {
+            self.heads.resize(priority + 1, NONE);
+        }
+        let head = self.heads[priority];
+        self.prev[i] = NONE;
+        self.next[i] = head;
+        if head != NONE {
+            self.prev[head] = i;
+        }
+        self.heads[priority] = i;
+        self.priorities[i] = priority;
+        self.cursor = self.cursor.min(priority);
+    }
+
+    /// Insert an item, or move it if it is already queued
+    pub fn push(&mut self, item: T, priority: usize) {
+        let i = (self.index)(item);
+        if self.priorities[i] == NONE {
+            self.len += 1;
+        } else {
+            self.unlink(i);
+        }
+        self.items[i] = Some(item);
+        self.link(i, priority);
+    }
+
+    pub fn modify_key(&mut self, item: T, to: usize) {
+        if self.priority(item).is_some() {
+            self.push(item, to);
+        }
+    }
+
+    pub fn decrease_key(&mut self, item: T, by: usize) {
+        let Some(current_priority) = self.priority(item) else {
+            return;
+        };
+        self.push(item, current_priority.saturating_sub(by));
+    }
+
+    pub fn priority(&self, item: T) -> Option<usize> {
+        let p = self.priorities[(self.index)(item)];
+        (p != NONE).then_some(p)
+    }
+
+    fn min_bucket(&self) -> Option<usize> {
+        (self.cursor..self.heads.len()).find(|&p| self.heads[p] != NONE)
+    }
+
+    pub fn peek_min(&self) -> Option<Node<T>> {
+        let p = self.min_bucket()?;
+        self.items[self.heads[p]].map(|x| Node {
+            value: x,
+            priority: p,
+        })
+    }
+
+    pub fn pop_min(&mut self) -> Option<Node<T>> {
+        let p = self.min_bucket()?;
+        self.cursor = p;
+        let i = self.heads[p];
+        self.unlink(i);
+        self.priorities[i] = NONE;
+        self.len -= 1;
+        self.items[i].take().map(|x| Node {
+            value: x,
+            priority: p,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl IndexedBucketQueue<Point, fn(Point) -> usize> {
+    /// A queue over the cells of `grid`, indexed in row-major order
+    pub fn for_grid<C>(grid: &Grid<C>) -> IndexedBucketQueue<Point, impl Fn(Point) -> usize> {
+        let dimension = grid.dimension();
+        IndexedBucketQueue::new((dimension.x * dimension.y) as usize, move |p: Point| {
+            (p.y * dimension.x + p.x) as usize
+        })
+    }
+}
+
+/// A radix heap for monotone priorities spanning the whole `usize` range.
+///
+/// Entries are kept in one bucket per bit position of their difference from the last
+/// popped priority. Changing a key pushes a new entry and leaves the old one to be
+/// discarded lazily. Priorities must never be set below that of the last popped item.
+pub struct RadixHeap<T: Element> {
+    buckets: [Vec<(T, usize)>; usize::BITS as usize + 1],
+    priorities: HashMap<T, usize>,
+    last: usize,
+}
+
+impl<T: Element> Default for RadixHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element> RadixHeap<T> {
+    pub fn new() -> RadixHeap<T> {
+        RadixHeap {
+            buckets: array::from_fn(|_| Vec::new()),
+            priorities: HashMap::new(),
+            last: 0,
+        }
+    }
+
+    fn bucket(&self, priority: usize) -> usize {
+        (usize::BITS - (priority ^ self.last).leading_zeros()) as usize
+    }
+
+    fn is_live(&self, (item, priority): (T, usize)) -> bool {
+        self.priorities.get(&item) == Some(&priority)
+    }
+
+    /// Insert an item, or move it if it is already queued
+    pub fn push(&mut self, item: T, priority: usize) {
+        assert!(
+            priority >= self.last,
+            "priority {priority} is below the current minimum {}",
+            self.last
+        );
+        self.priorities.insert(item, priority);
+        let b = self.bucket(priority);
+        self.buckets[b].push((item, priority));
+    }
+
+    pub fn modify_key(&mut self, item: T, to: usize) {
+        if self.priorities.contains_key(&item) {
+            self.push(item, to);
+        }
+    }
+
+    pub fn decrease_key(&mut self, item: T, by: usize) {
+        let Some(&current_priority) = self.priorities.get(&item) else {
+            return;
+        };
+        let to = current_priority.saturating_sub(by).max(self.last);
+        self.push(item, to);
+    }
+
+    pub fn priority(&self, item: T) -> Option<usize> {
+        self.priorities.get(&item).copied()
+    }
+
+    pub fn pop_min(&mut self) -> Option<Node<T>> {
+        loop {
+            while let Some(entry) = self.buckets[0].pop() {
+                if self.is_live(entry) {
+                    self.priorities.remove(&entry.0);
+                    return Some(Node {
+                        value: entry.0,
+                        priority: entry.1,
+                    });
+                }
+            }
+            if self.is_empty() {
+                return None;
+            }
+
+            let b = (1..self.buckets.len()).find(|&b| !self.buckets[b].is_empty())?;
+            let entries = std::mem::take(&mut self.buckets[b]);
+            let live: Vec<(T, usize)> = entries.into_iter().filter(|&e| self.is_live(e)).collect();
+            if let Some(min) = live.iter().map(|&(_, p)| p).min() {
+                self.last = min;
+            }
+            for (item, priority) in live {
+                let b = self.bucket(priority);
+                self.buckets[b].push((item, priority));
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.priorities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.priorities.is_empty()
+    }
+}
+
+/// The operations shared by every queue in this module, so algorithms can be
+/// generic over the queue and implementations switched by type alone
+pub trait PriorityQueue<T: Element> {
+    /// Insert an item, or move it if it is already queued
+    fn push(&mut self, item: T, priority: usize);
+    fn pop_min(&mut self) -> Option<Node<T>>;
+    /// Change the priority of a queued item; does nothing if it is not queued
+    fn modify_key(&mut self, item: T, to: usize);
+    fn decrease_key(&mut self, item: T, by: usize);
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+macro_rules! impl_priority_queue {
+    ($ty:ty, $($generics:tt)*) => {
+        impl<$($generics)*> PriorityQueue<T> for $ty {
+            fn push(&mut self, item: T, priority: usize) {
+                Self::push(self, item, priority)
+            }
+
+            fn pop_min(&mut self) -> Option<Node<T>> {
+                Self::pop_min(self)
+            }
+
+            fn modify_key(&mut self, item: T, to: usize) {
+                Self::modify_key(self, item, to)
+            }
+
+            fn decrease_key(&mut self, item: T, by: usize) {
+                Self::decrease_key(self, item, by)
+            }
+
+            fn len(&self) -> usize {
+                Self::len(self)
+            }
+        }
+    };
+}
+
+impl_priority_queue!(BucketQueue<T, N>, T: Element, const N: usize);
+impl_priority_queue!(MonotoneBucketQueue<T>, T: Element);
+impl_priority_queue!(IndexedBucketQueue<T, F>, T: Element, F: Fn(T) -> usize);
+impl_priority_queue!(RadixHeap<T>, T: Element);
+
 pub struct Bucket<T: Element> {
     items: HashSet<T>,
 }
@@ -127,6 +532,140 @@ mod tests {
         assert_eq!(x.priority, 0);
     }
 
+    #[test]
+    fn test_monotone() {
+        let mut queue = MonotoneBucketQueue::new();
+        assert!(queue.pop_min().is_none());
+
+        queue.push("banana", 3);
+        queue.push("apple", 1);
+        queue.push("mango", 1000);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.peek_min().map(|x| x.value), Some("apple"));
+
+        let x = queue.pop_min().unwrap();
+        assert_eq!((x.value, x.priority), ("apple", 1));
+
+        queue.push("kiwi", 2);
+        queue.decrease_key("mango", 998);
+        assert_eq!(queue.priority("mango"), Some(2));
+        queue.modify_key("banana", 5);
+        queue.modify_key("orange", 1);
+        assert_eq!(queue.priority("orange"), None);
+
+        let mut two_set = HashSet::new();
+        two_set.insert(queue.pop_min().unwrap().value);
+        two_set.insert(queue.pop_min().unwrap().value);
+        assert_eq!(two_set, HashSet::from(["kiwi", "mango"]));
+
+        let x = queue.pop_min().unwrap();
+        assert_eq!((x.value, x.priority), ("banana", 5));
+        assert!(queue.is_empty());
+        assert!(queue.peek_min().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_monotone_violation() {
+        let mut queue = MonotoneBucketQueue::new();
+        queue.push(1, 4);
+        queue.pop_min();
+        queue.push(2, 3);
+    }
+
+    #[test]
+    fn test_indexed() {
+        let mut queue = IndexedBucketQueue::new(10, |x: usize| x);
+        for (item, priority) in [(3, 4), (7, 1), (2, 4), (9, 8)] {
+            queue.push(item, priority);
+        }
+        assert_eq!(queue.len(), 4);
+
+        queue.modify_key(9, 0);
+        queue.decrease_key(3, 1);
+        queue.modify_key(5, 0);
+        assert_eq!(queue.priority(5), None);
+
+        let order: Vec<(usize, usize)> = std::iter::from_fn(|| queue.pop_min())
+            .map(|x| (x.value, x.priority))
+            .collect();
+        assert_eq!(order, [(9, 0), (7, 1), (3, 3), (2, 4)]);
+        assert!(queue.is_empty());
+
+        queue.push(4, 2);
+        assert_eq!(queue.peek_min().map(|x| x.value), Some(4));
+    }
+
+    #[test]
+    fn test_indexed_grid() {
+        let grid = Grid::new_with_dimensions_uniform(lina::vec2(3, 2), ());
+        let mut queue = IndexedBucketQueue::for_grid(&grid);
+        for p in grid.iter_coordinates() {
+            queue.push(p, (p.x + p.y) as usize);
+        }
+        queue.push(Point::new(2, 1), 0);
+
+        let x = queue.pop_min().unwrap();
+        assert_eq!((x.value, x.priority), (Point::new(2, 1), 0));
+        let x = queue.pop_min().unwrap();
+        assert_eq!((x.value, x.priority), (Point::new(0, 0), 0));
+        assert_eq!(queue.len(), 4);
+    }
+
+    #[test]
+    fn test_radix_heap() {
+        let mut heap = RadixHeap::new();
+        assert!(heap.pop_min().is_none());
+
+        heap.push("far", 1_000_000_000_000);
+        heap.push("near", 5);
+        heap.push("middle", 70_000);
+        heap.push("stale", 90);
+        heap.modify_key("stale", 80_000);
+        heap.decrease_key("far", 999_999_000_000);
+        assert_eq!(heap.len(), 4);
+
+        let order: Vec<(&str, usize)> = std::iter::from_fn(|| heap.pop_min())
+            .map(|x| (x.value, x.priority))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("near", 5),
+                ("middle", 70_000),
+                ("stale", 80_000),
+                ("far", 1_000_000)
+            ]
+        );
+        assert!(heap.is_empty());
+    }
+
+    fn drain<Q: PriorityQueue<u32>>(mut queue: Q) -> Vec<usize> {
+        for (i, p) in [7, 2, 9, 4, 4, 0].into_iter().enumerate() {
+            queue.push(i as u32, p);
+        }
+        queue.modify_key(2, 1);
+        queue.decrease_key(0, 7);
+        std::iter::from_fn(|| queue.pop_min())
+            .map(|x| x.priority)
+            .collect()
+    }
+
+    #[test]
+    fn test_interchangeable() {
+        let expected = [0, 0, 1, 2, 4, 4];
+        assert_eq!(
+            drain(BucketQueue::<u32, 10>::create(HashMap::new())),
+            expected
+        );
+        assert_eq!(drain(MonotoneBucketQueue::new()), expected);
+        assert_eq!(
+            drain(IndexedBucketQueue::new(6, |x: u32| x as usize)),
+            expected
+        );
+        assert_eq!(drain(RadixHeap::new()), expected);
+    }
+
     #[test]
     fn test_pop_order() {
         let mut queue = create_queue();
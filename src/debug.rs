@@ -5,7 +5,7 @@ pub trait PrintBytes {
 
 impl PrintBytes for &[u8] {
     fn display(&self) -> String {
-        String::from_utf8_lossy(&self).to_string()
+        String::from_utf8_lossy(self).to_string()
     }
     fn print(&self) {
         println!("{}", self.display());
@@ -1,81 +1,255 @@
-// use std::{
-//     collections::{HashMap, VecDeque},
-//     hash::Hash,
-//     ops::{Add, Sub},
-// };
-
-// use num::{Zero, zero};
-
-// use crate::grid::{Grid, Point};
-
-// pub trait Graph {
-//     type Node: Hash + PartialEq + Eq + Clone + Copy;
-//     type Weight: PartialEq + PartialOrd + Sub + Add + Zero;
-
-//     fn adjacent(&self, v: Self::Node) -> Vec<(Self::Node, Self::Weight)>;
-
-//     fn are_adjacent(&self, u: Self::Node, v: Self::Node) -> Option<Self::Weight>;
-
-//     fn bfs(
-//         &self,
-//         src: Self::Node,
-//     ) -> HashMap<Self::Node, HashMap<Self::Node, Option<Self::Weight>>> {
-//         let mut queue: VecDeque<Self::Node> = VecDeque::new();
-//         let mut visited: HashMap<Self::Node, HashMap<Self::Node, Option<Self::Weight>>> =
-//             HashMap::new();
-//         visited
-//             .entry(src)
-//             .or_insert(HashMap::new())
-//             .insert(src, Some(zero()));
-//         queue.push_back(src);
-//         while let Some(u) = queue.pop_front() {
-//             for v in self.adjacent(u) {
-                
-//             }
-//         }
-//         visited
-//     }
-// }
-
-// pub struct GridGraph<C> {
-//     grid: Grid<C>,
-// }
-
-// impl<C> GridGraph<C> {
-//     pub fn new(grid: Grid<C>) -> GridGraph<C> {
-//         GridGraph { grid }
-//     }
-// }
-
-// impl<C> Graph for GridGraph<C>
-// where
-//     C: Copy + PartialOrd + PartialEq + Sub<Output = C> + num::Zero,
-// {
-//     type Node = Point;
-//     type Weight = C;
-
-//     fn adjacent(&self, v: Self::Node) -> Vec<(Self::Node, Self::Weight)> {
-//         self.grid
-//             .neighbours(v)
-//             .iter()
-//             .map(|&(a, b)| (a, Self::weight(self.grid[v], *b)))
-//             .collect()
-//     }
-
-//     fn are_adjacent(&self, u: Self::Node, v: Self::Node) -> Option<Self::Weight> {
-//         if u.distance2_from(v) > 1 {
-//             None
-//         } else {
-//             Some(GridGraph::weight(self.grid[u], self.grid[v]))
-//         }
-//     }
-// }
-
-// impl<C> GridGraph<C>
-// where
-//     C: Sub<Output = C>,
-// {
-//     fn weight(u: C, v: C) -> C {
-//         v - u
-//     }
-// }
+pub mod astar;
+pub mod bfs;
+pub mod biconnected;
+pub mod clique;
+pub mod compress;
+pub mod dijkstra;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod flow;
+pub mod longest;
+pub mod matching;
+pub mod scc;
+pub mod topo;
+
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+
+use arrayvec::ArrayVec;
+use num::Zero;
+
+use crate::grid::{Grid, GridTrait, Point, sparse::SparseGrid};
+
+pub trait Node: Hash + Eq + Copy {}
+impl<T: Hash + Eq + Copy> Node for T {}
+
+pub trait Weight: Copy + Ord + Zero {}
+impl<T: Copy + Ord + Zero> Weight for T {}
+
+/// A directed, weighted graph described by its adjacency.
+///
+/// Undirected graphs are represented by listing each edge from both ends.
+pub trait Graph {
+    type Node: Node;
+    type Weight: Weight;
+
+    fn adjacent(&self, v: Self::Node) -> Vec<(Self::Node, Self::Weight)>;
+
+    /// Adjacent nodes with the edge weights discarded.
+    ///
+    /// Named apart from [`Grid::neighbours`], which also includes diagonal cells.
+    fn successors(&self, v: Self::Node) -> Vec<Self::Node> {
+        self.adjacent(v).into_iter().map(|(n, _)| n).collect()
+    }
+}
+
+impl<G: Graph + ?Sized> Graph for &G {
+    type Node = G::Node;
+    type Weight = G::Weight;
+
+    fn adjacent(&self, v: Self::Node) -> Vec<(Self::Node, Self::Weight)> {
+        (**self).adjacent(v)
+    }
+}
+
+/// Every in-bounds orthogonal neighbour is adjacent with unit weight.
+///
+/// Use [`from_grid`] to exclude walls.
+impl<C> Graph for Grid<C> {
+    type Node = Point;
+    type Weight = usize;
+
+    fn adjacent(&self, v: Point) -> Vec<(Point, usize)> {
+        Grid::adjacent(self, v)
+            .into_iter()
+            .map(|(p, _)| (p, 1))
+            .collect()
+    }
+}
+
+/// Only cells that have been written to are nodes, so the graph stays finite.
+impl<C: Clone> Graph for SparseGrid<C> {
+    type Node = Point;
+    type Weight = usize;
+
+    fn adjacent(&self, v: Point) -> Vec<(Point, usize)> {
+        GridTrait::adjacent(self, v)
+            .into_iter()
+            .filter(|&(p, _)| self.contains(p))
+            .map(|(p, _)| (p, 1))
+            .collect()
+    }
+}
+
+impl<N: Node, W: Weight, S: BuildHasher> Graph for HashMap<N, Vec<(N, W)>, S> {
+    type Node = N;
+    type Weight = W;
+
+    fn adjacent(&self, v: N) -> Vec<(N, W)> {
+        self.get(&v).cloned().unwrap_or_default()
+    }
+}
+
+/// A graph defined by a closure returning the weighted adjacency of a node
+pub struct FromFn<N, W, F> {
+    f: F,
+    _marker: PhantomData<fn(N) -> W>,
+}
+
+pub fn from_fn<N, W, F, I>(f: F) -> FromFn<N, W, F>
+where
+    N: Node,
+    W: Weight,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = (N, W)>,
+{
+    FromFn {
+        f,
+        _marker: PhantomData,
+    }
+}
+
+impl<N, W, F, I> Graph for FromFn<N, W, F>
+where
+    N: Node,
+    W: Weight,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = (N, W)>,
+{
+    type Node = N;
+    type Weight = W;
+
+    fn adjacent(&self, v: N) -> Vec<(N, W)> {
+        (self.f)(v).into_iter().collect()
+    }
+}
+
+/// A graph with unit weights defined by a closure returning the neighbours of a node
+pub struct FromNeighbours<N, F> {
+    f: F,
+    _marker: PhantomData<fn(N)>,
+}
+
+pub fn from_neighbours<N, F, I>(f: F) -> FromNeighbours<N, F>
+where
+    N: Node,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    FromNeighbours {
+        f,
+        _marker: PhantomData,
+    }
+}
+
+impl<N, F, I> Graph for FromNeighbours<N, F>
+where
+    N: Node,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Node = N;
+    type Weight = usize;
+
+    fn adjacent(&self, v: N) -> Vec<(N, usize)> {
+        (self.f)(v).into_iter().map(|n| (n, 1)).collect()
+    }
+
+    fn successors(&self, v: N) -> Vec<N> {
+        (self.f)(v).into_iter().collect()
+    }
+}
+
+/// The orthogonally connected cells of `grid` satisfying `passable`, with unit weights.
+///
+/// A cell that is not passable itself still reaches its passable neighbours.
+pub fn from_grid<C>(
+    grid: &Grid<C>,
+    passable: impl Fn(&C) -> bool,
+) -> FromNeighbours<Point, impl Fn(Point) -> ArrayVec<Point, 4>> {
+    from_neighbours(move |p| {
+        grid.adjacent(p)
+            .into_iter()
+            .filter(|(_, c)| passable(c))
+            .map(|(q, _)| q)
+            .collect()
+    })
+}
+
+/// Walk a parent map back from `target`, returning the path from its root to `target`
+pub(crate) fn trace_parents<N: Node>(parent: &HashMap<N, N>, target: N) -> Vec<N> {
+    let mut path = vec![target];
+    let mut current = target;
+    while let Some(&p) = parent.get(&current) {
+        path.push(p);
+        current = p;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::point2;
+
+    use super::*;
+
+    #[test]
+    fn test_grid_adjacent() {
+        let g = Grid::read("#..\n...\n..#\n", |x| x);
+
+        let mut corner = Graph::adjacent(&g, point2(0, 0));
+        corner.sort_by_key(|(p, _)| (p.y, p.x));
+        assert_eq!(corner, [(point2(1, 0), 1), (point2(0, 1), 1)]);
+
+        assert_eq!(Graph::adjacent(&g, point2(1, 1)).len(), 4);
+    }
+
+    #[test]
+    fn test_sparse_grid_adjacent() {
+        let mut g = SparseGrid::new('.');
+        g[point2(0, 0)] = '#';
+        g[point2(1, 0)] = '#';
+        g[point2(5, 5)] = '#';
+
+        assert_eq!(Graph::adjacent(&g, point2(0, 0)), [(point2(1, 0), 1)]);
+        assert!(Graph::adjacent(&g, point2(5, 5)).is_empty());
+    }
+
+    #[test]
+    fn test_adjacency_list() {
+        let g = HashMap::from([('a', vec![('b', 3), ('c', 1)]), ('b', vec![('c', 1)])]);
+
+        assert_eq!(g.adjacent('a'), [('b', 3), ('c', 1)]);
+        assert_eq!(g.successors('b'), ['c']);
+        assert!(g.adjacent('c').is_empty());
+    }
+
+    #[test]
+    fn test_closures() {
+        let g = Grid::read("..#\n.##\n...\n", |x| x);
+        let open = from_neighbours(|p| {
+            g.adjacent(p)
+                .into_iter()
+                .filter(|&(_, &c)| c == '.')
+                .map(|(p, _)| p)
+        });
+        assert_eq!(
+            open.adjacent(point2(0, 0)),
+            [(point2(1, 0), 1), (point2(0, 1), 1)]
+        );
+        assert_eq!(open.successors(point2(1, 0)), [point2(0, 0)]);
+
+        let collatz = from_fn(|n: u64| {
+            if n.is_multiple_of(2) {
+                Some((n / 2, 1))
+            } else {
+                Some((3 * n + 1, 1))
+            }
+        });
+        assert_eq!(collatz.successors(7), [22]);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use num::{NumCast, Zero};
+
+use crate::{
+    MoreNormDistance,
+    graph::{Graph, Node, Weight, dijkstra::State, trace_parents},
+    grid::Point,
+};
+
+/// The result of an A* search towards a goal
+pub struct AStar<N: Node, W: Weight> {
+    pub start: N,
+    /// The goal node that was reached and its cost
+    pub goal: Option<(N, W)>,
+    pub distance: HashMap<N, W>,
+    pub parent: HashMap<N, N>,
+    /// Number of expansions before the goal was reached, counting reopened nodes again
+    pub expanded: usize,
+}
+
+impl<N: Node, W: Weight> AStar<N, W> {
+    /// The path from the start to the reached goal, both inclusive
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.goal.map(|(g, _)| trace_parents(&self.parent, g))
+    }
+}
+
+/// Search for the cheapest path to any node satisfying `goal`.
+///
+/// `heuristic` must never overestimate the remaining cost for the result to be optimal.
+/// Nodes are reopened when a cheaper path to them is found, so it need not be consistent.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: impl Fn(G::Node) -> bool,
+    heuristic: impl Fn(G::Node) -> G::Weight,
+) -> AStar<G::Node, G::Weight> {
+    let mut result = AStar {
+        start,
+        goal: None,
+        distance: HashMap::from([(start, G::Weight::zero())]),
+        parent: HashMap::new(),
+        expanded: 0,
+    };
+    let mut heap = BinaryHeap::from([State {
+        cost: heuristic(start),
+        node: (start, G::Weight::zero()),
+    }]);
+
+    while let Some(State {
+        node: (u, cost), ..
+    }) = heap.pop()
+    {
+        // A cheaper path to `u` was found after this entry was pushed
+        if result.distance[&u] < cost {
+            continue;
+        }
+        if goal(u) {
+            result.goal = Some((u, cost));
+            break;
+        }
+        result.expanded += 1;
+        for (v, w) in graph.adjacent(u) {
+            let next = cost + w;
+            if result.distance.get(&v).is_some_and(|&d| d <= next) {
+                continue;
+            }
+            result.distance.insert(v, next);
+            result.parent.insert(v, u);
+            heap.push(State {
+                cost: next + heuristic(v),
+                node: (v, next),
+            });
+        }
+    }
+    result
+}
+
+/// Admissible for orthogonal movement with unit cost
+pub fn manhattan<W: Weight + NumCast>(goal: Point) -> impl Fn(Point) -> W {
+    move |p| num::cast(p.distance_manhattan(goal)).expect("distance fits in the weight type")
+}
+
+/// Admissible for movement in all eight directions with unit cost
+pub fn chebyshev<W: Weight + NumCast>(goal: Point) -> impl Fn(Point) -> W {
+    move |p| num::cast(p.distance_inf(goal)).expect("distance fits in the weight type")
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::{point2, vec2};
+
+    use super::*;
+    use crate::{
+        graph::{dijkstra::dijkstra, fixtures::walled_maze, from_grid, from_neighbours},
+        grid::Grid,
+    };
+
+    #[test]
+    fn test_heuristics_agree() {
+        let g = walled_maze();
+        let open = from_grid(&g, |&c| c != '#');
+        let start = point2(0, 0);
+        let end = point2(5, 4);
+        let expected = dijkstra(&open, start).distance[&end];
+
+        let blind = astar(&open, start, |p| p == end, |_| 0);
+        let guided = astar(&open, start, |p| p == end, manhattan(end));
+
+        assert_eq!(blind.goal, Some((end, expected)));
+        assert_eq!(guided.goal, Some((end, expected)));
+        assert!(guided.expanded < blind.expanded);
+
+        let path = guided.path().unwrap();
+        assert_eq!(path.len(), expected + 1);
+        assert_eq!((path[0], path[expected]), (start, end));
+    }
+
+    #[test]
+    fn test_chebyshev() {
+        let king = from_neighbours(|p: Point| crate::grid::NEIGHBOURS.map(|d| p + d));
+        let end = point2(7, -3);
+        let result = astar(&king, point2(0, 0), |p| p == end, chebyshev(end));
+        assert_eq!(result.goal, Some((end, 7usize)));
+        let blind = astar(&king, point2(0, 0), |p| p == end, |_| 0usize);
+        assert!(result.expanded < blind.expanded);
+
+        assert_eq!(manhattan::<u64>(end)(point2(1, 1)), 10);
+        assert_eq!(chebyshev::<i64>(end)(point2(0, 0) + vec2(1, 1)), 6);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let g = Grid::read(".#.\n##.\n", |x| x);
+        let open = from_grid(&g, |&c| c != '#');
+        let result = astar(
+            &open,
+            point2(0, 0),
+            |p| p == point2(2, 1),
+            manhattan::<usize>(point2(2, 1)),
+        );
+        assert_eq!(result.goal, None);
+        assert_eq!(result.path(), None);
+        assert_eq!(result.expanded, 1);
+    }
+
+    #[test]
+    fn test_inconsistent_heuristic() {
+        let g = HashMap::from([
+            ('s', vec![('a', 1), ('b', 4)]),
+            ('a', vec![('b', 1)]),
+            ('b', vec![('g', 5)]),
+        ]);
+        let h = |n| if n == 'a' { 5 } else { 0 };
+        let result = astar(&g, 's', |n| n == 'g', h);
+        assert_eq!(result.goal, Some(('g', 7)));
+        assert_eq!(result.path().unwrap(), ['s', 'a', 'b', 'g']);
+    }
+}
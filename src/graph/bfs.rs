@@ -0,0 +1,131 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::graph::{Graph, Node, trace_parents};
+
+/// The result of a breadth first search from a single source
+pub struct Bfs<N: Node> {
+    pub start: N,
+    pub distance: HashMap<N, usize>,
+    pub parent: HashMap<N, N>,
+}
+
+impl<N: Node> Bfs<N> {
+    /// Shortest path from the start to `target`, both inclusive
+    pub fn path_to(&self, target: N) -> Option<Vec<N>> {
+        if !self.distance.contains_key(&target) {
+            return None;
+        }
+        Some(trace_parents(&self.parent, target))
+    }
+
+    /// All reached nodes exactly `k` steps from the start
+    pub fn at_distance(&self, k: usize) -> impl Iterator<Item = N> {
+        self.distance
+            .iter()
+            .filter(move |&(_, &d)| d == k)
+            .map(|(&n, _)| n)
+    }
+}
+
+pub fn bfs<G: Graph>(graph: &G, start: G::Node) -> Bfs<G::Node> {
+    search(graph, start, |_| false, usize::MAX).0
+}
+
+/// Search until a node satisfying `goal` is dequeued, returning it alongside the partial search
+pub fn bfs_until<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: impl Fn(G::Node) -> bool,
+) -> (Bfs<G::Node>, Option<G::Node>) {
+    search(graph, start, goal, usize::MAX)
+}
+
+/// Only explore nodes at most `max_depth` steps away, needed for infinite graphs
+pub fn bfs_limited<G: Graph>(graph: &G, start: G::Node, max_depth: usize) -> Bfs<G::Node> {
+    search(graph, start, |_| false, max_depth).0
+}
+
+fn search<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: impl Fn(G::Node) -> bool,
+    max_depth: usize,
+) -> (Bfs<G::Node>, Option<G::Node>) {
+    let mut result = Bfs {
+        start,
+        distance: HashMap::from([(start, 0)]),
+        parent: HashMap::new(),
+    };
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(u) = queue.pop_front() {
+        if goal(u) {
+            return (result, Some(u));
+        }
+        let d = result.distance[&u];
+        if d >= max_depth {
+            continue;
+        }
+        for v in graph.successors(u) {
+            if result.distance.contains_key(&v) {
+                continue;
+            }
+            result.distance.insert(v, d + 1);
+            result.parent.insert(v, u);
+            queue.push_back(v);
+        }
+    }
+    (result, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::point2;
+
+    use super::*;
+    use crate::{
+        graph::{fixtures::small_maze, from_grid, from_neighbours},
+        grid::Point,
+    };
+
+    #[test]
+    fn test_grid_path() {
+        let g = small_maze();
+        let open = from_grid(&g, |&c| c != '#');
+        let start = g.position(|&c| c == 'S').unwrap();
+        let end = g.position(|&c| c == 'E').unwrap();
+
+        let result = bfs(&open, start);
+        assert_eq!(result.distance[&end], 11);
+
+        let path = result.path_to(end).unwrap();
+        assert_eq!(path.len(), 12);
+        assert_eq!(path[0], start);
+        assert_eq!(path[11], end);
+        assert!(path.windows(2).all(|w| w[0].distance2_from(w[1]) == 1));
+
+        assert_eq!(result.path_to(point2(2, 0)), None);
+    }
+
+    #[test]
+    fn test_until() {
+        let g = small_maze();
+        let open = from_grid(&g, |&c| c != '#');
+        let (result, found) = bfs_until(&open, point2(0, 0), |p| g[p] == 'E');
+        assert_eq!(found, Some(point2(5, 2)));
+        assert_eq!(result.distance[&point2(5, 2)], 11);
+    }
+
+    #[test]
+    fn test_at_distance_infinite() {
+        let plane = from_neighbours(|p: Point| {
+            crate::grid::UP_RIGHT_DOWN_LEFT
+                .into_iter()
+                .map(move |d| p + d)
+        });
+        let result = bfs_limited(&plane, point2(0, 0), 3);
+        assert_eq!(result.at_distance(3).count(), 12);
+        assert_eq!(result.at_distance(2).count(), 8);
+        assert_eq!(result.distance.len(), 1 + 4 + 8 + 12);
+    }
+}
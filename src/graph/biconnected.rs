@@ -0,0 +1,210 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    graph::{Graph, Node, from_grid},
+    grid::{Grid, Point},
+};
+
+/// The cut structure of an undirected graph
+pub struct Biconnected<N: Node> {
+    /// Edges whose removal disconnects their endpoints, as `(parent, child)` in the DFS tree
+    pub bridges: Vec<(N, N)>,
+    /// Nodes whose removal disconnects the graph
+    pub articulation_points: HashSet<N>,
+    /// The nodes of each biconnected component; articulation points appear in several
+    pub components: Vec<Vec<N>>,
+}
+
+struct Frame<N> {
+    node: N,
+    parent: Option<N>,
+    neighbours: Vec<N>,
+    next: usize,
+    skipped_parent: bool,
+    children: usize,
+}
+
+fn enter<G: Graph>(
+    graph: &G,
+    discovered: &mut HashMap<G::Node, usize>,
+    low: &mut HashMap<G::Node, usize>,
+    v: G::Node,
+    parent: Option<G::Node>,
+) -> Frame<G::Node> {
+    let i = discovered.len();
+    discovered.insert(v, i);
+    low.insert(v, i);
+    Frame {
+        node: v,
+        parent,
+        neighbours: graph.successors(v),
+        next: 0,
+        skipped_parent: false,
+        children: 0,
+    }
+}
+
+/// Tarjan's lowlink algorithm over every node reachable from `nodes`.
+///
+/// Each undirected edge must be listed from both ends.
+pub fn biconnected<G: Graph>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> Biconnected<G::Node> {
+    let mut result = Biconnected {
+        bridges: Vec::new(),
+        articulation_points: HashSet::new(),
+        components: Vec::new(),
+    };
+    let mut discovered: HashMap<G::Node, usize> = HashMap::new();
+    let mut low: HashMap<G::Node, usize> = HashMap::new();
+    let mut edges: Vec<(G::Node, G::Node)> = Vec::new();
+
+    for root in nodes {
+        if discovered.contains_key(&root) {
+            continue;
+        }
+        let mut calls = vec![enter(graph, &mut discovered, &mut low, root, None)];
+
+        while let Some(frame) = calls.last_mut() {
+            let v = frame.node;
+            if let Some(&w) = frame.neighbours.get(frame.next) {
+                frame.next += 1;
+                // skip the tree edge back to the parent once, so parallel edges still count
+                if Some(w) == frame.parent && !frame.skipped_parent {
+                    frame.skipped_parent = true;
+                    continue;
+                }
+                match discovered.get(&w) {
+                    None => {
+                        frame.children += 1;
+                        edges.push((v, w));
+                        let child = enter(graph, &mut discovered, &mut low, w, Some(v));
+                        calls.push(child);
+                    }
+                    Some(&d) if d < discovered[&v] => {
+                        edges.push((v, w));
+                        let l = low[&v].min(d);
+                        low.insert(v, l);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            let frame = calls.pop().expect("the frame was just inspected");
+            let Some(p) = frame.parent else {
+                if frame.children >= 2 {
+                    result.articulation_points.insert(v);
+                }
+                continue;
+            };
+            let l = low[&p].min(low[&v]);
+            low.insert(p, l);
+            if low[&v] > discovered[&p] {
+                result.bridges.push((p, v));
+            }
+            if low[&v] >= discovered[&p] {
+                if calls.last().is_some_and(|f| f.parent.is_some()) {
+                    result.articulation_points.insert(p);
+                }
+                let mut component = HashSet::new();
+                while let Some((a, b)) = edges.pop() {
+                    component.insert(a);
+                    component.insert(b);
+                    if (a, b) == (p, v) {
+                        break;
+                    }
+                }
+                result.components.push(component.into_iter().collect());
+            }
+        }
+    }
+    result
+}
+
+/// [`biconnected`] over the orthogonally connected cells of `grid` satisfying `passable`
+pub fn grid_biconnected<C>(grid: &Grid<C>, passable: impl Fn(&C) -> bool) -> Biconnected<Point> {
+    biconnected(
+        &from_grid(grid, &passable),
+        grid.iter_coordinates().filter(|&p| passable(&grid[p])),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::point2;
+
+    use super::*;
+    use crate::graph::fixtures::undirected;
+
+    #[test]
+    fn test_bridges_and_articulation_points() {
+        // two triangles joined by the bridge 2-3, with a pendant 6 off 5
+        let g = undirected(&[
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 0, 1),
+            (2, 3, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 3, 1),
+            (5, 6, 1),
+        ]);
+        let result = biconnected(&g, [0]);
+
+        let mut bridges: Vec<(u32, u32)> = result
+            .bridges
+            .iter()
+            .map(|&(a, b)| crate::sort2((a, b)))
+            .collect();
+        bridges.sort();
+        assert_eq!(bridges, [(2, 3), (5, 6)]);
+        assert_eq!(result.articulation_points, HashSet::from([2, 3, 5]));
+
+        let mut components: Vec<Vec<u32>> = result
+            .components
+            .into_iter()
+            .map(|mut c| {
+                c.sort();
+                c
+            })
+            .collect();
+        components.sort();
+        assert_eq!(
+            components,
+            [vec![0, 1, 2], vec![2, 3], vec![3, 4, 5], vec![5, 6]]
+        );
+    }
+
+    #[test]
+    fn test_parallel_edges() {
+        let g = undirected(&[(0, 1, 1), (0, 1, 1), (1, 2, 1)]);
+        let result = biconnected(&g, [0]);
+        assert_eq!(result.bridges, [(1, 2)]);
+        assert_eq!(result.articulation_points, HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_grid() {
+        let g = Grid::read(
+            "\
+...#...
+.#.#.#.
+.......
+###.###
+..#.#..
+",
+            |x| x,
+        );
+        let result = grid_biconnected(&g, |&c| c == '.');
+
+        assert_eq!(
+            result.articulation_points,
+            HashSet::from([point2(2, 2), point2(3, 2), point2(4, 2), point2(3, 3)])
+        );
+        // four on the corridor and one in each pair of cells along the bottom
+        assert_eq!(result.bridges.len(), 6);
+        assert!(result.bridges.contains(&(point2(3, 2), point2(3, 3))));
+    }
+}
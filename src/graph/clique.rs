@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+use crate::graph::{Graph, Node};
+
+/// Dense ids in sorted node order with symmetric neighbour sets, ignoring self loops
+fn undirected<G>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> (Vec<G::Node>, Vec<HashSet<usize>>)
+where
+    G: Graph,
+    G::Node: Ord,
+{
+    let mut nodes: Vec<G::Node> = nodes.into_iter().collect();
+    nodes.sort();
+    nodes.dedup();
+    let mut neighbours = vec![HashSet::new(); nodes.len()];
+    for (u, &n) in nodes.iter().enumerate() {
+        for m in graph.successors(n) {
+            if let Ok(v) = nodes.binary_search(&m)
+                && u != v
+            {
+                neighbours[u].insert(v);
+                neighbours[v].insert(u);
+            }
+        }
+    }
+    (nodes, neighbours)
+}
+
+fn bron_kerbosch(
+    neighbours: &[HashSet<usize>],
+    clique: &mut Vec<usize>,
+    mut candidates: HashSet<usize>,
+    mut excluded: HashSet<usize>,
+    out: &mut Vec<Vec<usize>>,
+) {
+    let Some(&pivot) = candidates
+        .iter()
+        .chain(&excluded)
+        .max_by_key(|&&u| candidates.intersection(&neighbours[u]).count())
+    else {
+        out.push(clique.clone());
+        return;
+    };
+    let branches: Vec<usize> = candidates.difference(&neighbours[pivot]).copied().collect();
+    for v in branches {
+        clique.push(v);
+        bron_kerbosch(
+            neighbours,
+            clique,
+            candidates.intersection(&neighbours[v]).copied().collect(),
+            excluded.intersection(&neighbours[v]).copied().collect(),
+            out,
+        );
+        clique.pop();
+        candidates.remove(&v);
+        excluded.insert(v);
+    }
+}
+
+fn to_nodes<N: Node + Ord>(nodes: &[N], mut ids: Vec<usize>) -> Vec<N> {
+    ids.sort_unstable();
+    ids.into_iter().map(|i| nodes[i]).collect()
+}
+
+/// Every maximal clique among `nodes`, using Bron–Kerbosch with pivoting.
+///
+/// Edges are treated as undirected. Each clique is sorted, and the cliques are sorted.
+pub fn maximal_cliques<G>(graph: &G, nodes: impl IntoIterator<Item = G::Node>) -> Vec<Vec<G::Node>>
+where
+    G: Graph,
+    G::Node: Ord,
+{
+    let (nodes, neighbours) = undirected(graph, nodes);
+    let mut out = Vec::new();
+    if nodes.is_empty() {
+        return Vec::new();
+    }
+    bron_kerbosch(
+        &neighbours,
+        &mut Vec::new(),
+        (0..nodes.len()).collect(),
+        HashSet::new(),
+        &mut out,
+    );
+    let mut cliques: Vec<Vec<G::Node>> = out.into_iter().map(|c| to_nodes(&nodes, c)).collect();
+    cliques.sort();
+    cliques
+}
+
+/// A largest clique among `nodes`, the lexicographically smallest on ties
+pub fn maximum_clique<G>(graph: &G, nodes: impl IntoIterator<Item = G::Node>) -> Vec<G::Node>
+where
+    G: Graph,
+    G::Node: Ord,
+{
+    maximal_cliques(graph, nodes)
+        .into_iter()
+        .rev()
+        .max_by_key(|c| c.len())
+        .unwrap_or_default()
+}
+
+/// Every triangle among `nodes`, each sorted, in sorted order
+pub fn triangles<G>(graph: &G, nodes: impl IntoIterator<Item = G::Node>) -> Vec<[G::Node; 3]>
+where
+    G: Graph,
+    G::Node: Ord,
+{
+    let (nodes, neighbours) = undirected(graph, nodes);
+    let mut out = Vec::new();
+    for u in 0..nodes.len() {
+        for &v in neighbours[u].iter().filter(|&&v| v > u) {
+            for &w in neighbours[v].iter().filter(|&&w| w > v) {
+                if neighbours[u].contains(&w) {
+                    out.push([nodes[u], nodes[v], nodes[w]]);
+                }
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn lan(links: &str) -> HashMap<&str, Vec<(&str, usize)>> {
+        let mut g: HashMap<&str, Vec<(&str, usize)>> = HashMap::new();
+        for link in links.split_whitespace() {
+            let (a, b) = link.split_once('-').unwrap();
+            g.entry(a).or_default().push((b, 1));
+        }
+        g
+    }
+
+    fn network() -> HashMap<&'static str, Vec<(&'static str, usize)>> {
+        lan("ka-co ta-co de-co ta-ka de-ta ka-de tc-kh qp-kh tc-qp ub-tc")
+    }
+
+    fn nodes(g: &HashMap<&'static str, Vec<(&'static str, usize)>>) -> HashSet<&'static str> {
+        g.iter()
+            .flat_map(|(&k, v)| v.iter().map(|&(n, _)| n).chain([k]))
+            .collect()
+    }
+
+    #[test]
+    fn test_triangles() {
+        let g = network();
+        let found = triangles(&g, nodes(&g));
+        assert_eq!(
+            found,
+            [
+                ["co", "de", "ka"],
+                ["co", "de", "ta"],
+                ["co", "ka", "ta"],
+                ["de", "ka", "ta"],
+                ["kh", "qp", "tc"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cliques() {
+        let g = network();
+        assert_eq!(
+            maximal_cliques(&g, nodes(&g)),
+            [
+                vec!["co", "de", "ka", "ta"],
+                vec!["kh", "qp", "tc"],
+                vec!["tc", "ub"]
+            ]
+        );
+        assert_eq!(maximum_clique(&g, nodes(&g)), ["co", "de", "ka", "ta"]);
+
+        let empty: HashMap<u8, Vec<(u8, usize)>> = HashMap::new();
+        assert!(maximal_cliques(&empty, []).is_empty());
+        assert!(maximum_clique(&empty, []).is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use lina::Vec2;
+
+use crate::{
+    graph::Graph,
+    grid::{Grid, Point, UP_RIGHT_DOWN_LEFT},
+};
+
+/// A grid maze reduced to its junctions, joined by edges weighted with corridor length
+pub struct Compressed {
+    /// The grid position of each node id
+    pub points: Vec<Point>,
+    pub index: HashMap<Point, usize>,
+    pub adjacency: Vec<Vec<(usize, usize)>>,
+}
+
+impl Compressed {
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+impl Graph for Compressed {
+    type Node = usize;
+    type Weight = usize;
+
+    fn adjacent(&self, v: usize) -> Vec<(usize, usize)> {
+        self.adjacency[v].clone()
+    }
+}
+
+/// Collapse the corridors of `grid` into a weighted junction graph.
+///
+/// Nodes are the passable cells with at least three passable neighbours, plus every
+/// point in `keep` such as the start and end. Corridors ending in a dead end are dropped.
+pub fn compress_corridors<C>(
+    grid: &Grid<C>,
+    passable: impl Fn(&C) -> bool,
+    keep: impl IntoIterator<Item = Point>,
+) -> Compressed {
+    compress_corridors_directed(grid, passable, keep, |_, _| true)
+}
+
+/// [`compress_corridors`] where a step in direction `d` between two cells is only allowed
+/// if `can_move` holds for `d` on both of them, giving one-way edges through slopes
+pub fn compress_corridors_directed<C>(
+    grid: &Grid<C>,
+    passable: impl Fn(&C) -> bool,
+    keep: impl IntoIterator<Item = Point>,
+    can_move: impl Fn(&C, Vec2<i32>) -> bool,
+) -> Compressed {
+    let open = |p: Point| grid.get(p).is_some_and(&passable);
+    let mut points: Vec<Point> = keep.into_iter().filter(|&p| open(p)).collect();
+    points.extend(
+        grid.iter_coordinates().filter(|&p| {
+            open(p) && UP_RIGHT_DOWN_LEFT.iter().filter(|&&d| open(p + d)).count() >= 3
+        }),
+    );
+    let mut index = HashMap::new();
+    points.retain(|&p| {
+        let id = index.len();
+        match index.entry(p) {
+            Entry::Vacant(e) => {
+                e.insert(id);
+                true
+            }
+            Entry::Occupied(_) => false,
+        }
+    });
+
+    let step = |from: Point, d: Vec2<i32>| {
+        let to = from + d;
+        (open(to) && can_move(&grid[from], d) && can_move(&grid[to], d)).then_some(to)
+    };
+
+    let adjacency = points
+        .iter()
+        .map(|&start| {
+            UP_RIGHT_DOWN_LEFT
+                .iter()
+                .filter_map(|&d| {
+                    let (mut previous, mut current) = (start, step(start, d)?);
+                    let mut length = 1;
+                    while !index.contains_key(&current) {
+                        let next = UP_RIGHT_DOWN_LEFT
+                            .iter()
+                            .filter_map(|&d| step(current, d))
+                            .find(|&n| n != previous)?;
+                        (previous, current) = (current, next);
+                        length += 1;
+                    }
+                    (current != start).then(|| (index[&current], length))
+                })
+                .collect()
+        })
+        .collect();
+
+    Compressed {
+        points,
+        index,
+        adjacency,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::{point2, vec2};
+
+    use super::*;
+    use crate::graph::fixtures::slope_maze;
+
+    #[test]
+    fn test_compress() {
+        let g = slope_maze();
+        let (start, end) = (point2(1, 0), point2(3, 5));
+        let compressed = compress_corridors(&g, |&c| c != '#', [start, end]);
+
+        assert_eq!(compressed.points[0], start);
+        assert_eq!(compressed.points[1], end);
+        let junctions: Vec<Point> = compressed.points[2..].to_vec();
+        assert_eq!(junctions, [point2(1, 1), point2(3, 1), point2(3, 3)]);
+        let (corner, a, b) = (2, 3, 4);
+
+        assert_eq!(compressed.adjacent(compressed.index[&start]), [(corner, 1)]);
+        assert_eq!(compressed.adjacent(compressed.index[&end]), [(b, 2)]);
+        assert_eq!(compressed.adjacent(corner), [(0, 1), (a, 2), (b, 4)]);
+
+        let mut between: Vec<(usize, usize)> = compressed
+            .adjacent(a)
+            .into_iter()
+            .filter(|&(n, _)| n == b)
+            .collect();
+        between.sort();
+        assert_eq!(between, [(b, 2), (b, 6)]);
+    }
+
+    #[test]
+    fn test_keep_junction() {
+        let g = slope_maze();
+        let junction = point2(1, 1);
+        let compressed = compress_corridors(&g, |&c| c != '#', [junction, junction]);
+
+        assert_eq!(compressed.points, [junction, point2(3, 1), point2(3, 3)]);
+        for (id, p) in compressed.points.iter().enumerate() {
+            assert_eq!(compressed.index[p], id);
+        }
+        assert_eq!(compressed.adjacent(0), [(1, 2), (2, 4)]);
+    }
+
+    #[test]
+    fn test_slopes() {
+        let g = slope_maze();
+        let (start, end) = (point2(1, 0), point2(3, 5));
+        let slope = |&c: &char, d| c != '>' || d == vec2(1, 0);
+        let compressed = compress_corridors_directed(&g, |&c| c != '#', [start, end], slope);
+
+        let (a, b) = (
+            compressed.index[&point2(3, 1)],
+            compressed.index[&point2(3, 3)],
+        );
+        // the corridor through the slope can only be walked eastwards, from b to a
+        assert_eq!(
+            compressed
+                .adjacent(b)
+                .iter()
+                .filter(|&&(n, _)| n == a)
+                .count(),
+            2
+        );
+        assert_eq!(
+            compressed
+                .adjacent(a)
+                .iter()
+                .filter(|&&(n, _)| n == b)
+                .count(),
+            1
+        );
+    }
+}
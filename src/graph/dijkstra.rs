@@ -0,0 +1,338 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use num::Zero;
+
+use crate::{
+    bucket::{BucketQueue, MonotoneBucketQueue, PriorityQueue},
+    graph::{Graph, Node, Weight, trace_parents},
+};
+
+/// The result of a single source shortest path search
+pub struct Dijkstra<N: Node, W: Weight> {
+    pub start: N,
+    pub distance: HashMap<N, W>,
+    /// The predecessor through which each node was first reached at its final distance
+    pub parent: HashMap<N, N>,
+    /// Every predecessor lying on some shortest path to each node
+    pub predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Node, W: Weight> Dijkstra<N, W> {
+    fn new(start: N) -> Self {
+        Dijkstra {
+            start,
+            distance: HashMap::from([(start, W::zero())]),
+            parent: HashMap::new(),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    /// Record the edge `u -> v` reaching `v` with total cost `cost`; true if `v` improved
+    fn relax(&mut self, u: N, v: N, cost: W) -> bool {
+        match self.distance.get(&v).map(|d| cost.cmp(d)) {
+            Some(Ordering::Greater) => false,
+            Some(Ordering::Equal) => {
+                if v != self.start {
+                    self.predecessors.entry(v).or_default().push(u);
+                }
+                false
+            }
+            None | Some(Ordering::Less) => {
+                self.distance.insert(v, cost);
+                self.parent.insert(v, u);
+                self.predecessors.insert(v, vec![u]);
+                true
+            }
+        }
+    }
+
+    /// One shortest path from the start to `target`, both inclusive
+    pub fn path_to(&self, target: N) -> Option<Vec<N>> {
+        if !self.distance.contains_key(&target) {
+            return None;
+        }
+        Some(trace_parents(&self.parent, target))
+    }
+
+    /// Every node on any shortest path from the start to the nearest of `targets`.
+    ///
+    /// Targets further away than the nearest one are ignored, so all states of an
+    /// end cell can be passed at once.
+    pub fn on_shortest_paths(&self, targets: impl IntoIterator<Item = N>) -> HashSet<N> {
+        let reached: Vec<(N, W)> = targets
+            .into_iter()
+            .filter_map(|t| self.distance.get(&t).map(|&d| (t, d)))
+            .collect();
+        let Some(best) = reached.iter().map(|&(_, d)| d).min() else {
+            return HashSet::new();
+        };
+
+        let mut stack: Vec<N> = reached
+            .into_iter()
+            .filter(|&(_, d)| d == best)
+            .map(|(t, _)| t)
+            .collect();
+        let mut seen: HashSet<N> = stack.iter().copied().collect();
+        while let Some(v) = stack.pop() {
+            for &u in self.predecessors.get(&v).into_iter().flatten() {
+                if seen.insert(u) {
+                    stack.push(u);
+                }
+            }
+        }
+        seen
+    }
+}
+
+pub(super) struct State<N, W> {
+    pub(super) cost: W,
+    pub(super) node: N,
+}
+
+impl<N, W: Ord> Ord for State<N, W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+
+impl<N, W: Ord> PartialOrd for State<N, W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, W: Ord> PartialEq for State<N, W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl<N, W: Ord> Eq for State<N, W> {}
+
+/// Shortest paths using a binary heap, for arbitrary non-negative weights
+pub fn dijkstra<G: Graph>(graph: &G, start: G::Node) -> Dijkstra<G::Node, G::Weight> {
+    let mut result = Dijkstra::new(start);
+    let mut heap = BinaryHeap::from([State {
+        cost: G::Weight::zero(),
+        node: start,
+    }]);
+    let mut settled = HashSet::new();
+
+    while let Some(State { cost, node: u }) = heap.pop() {
+        if !settled.insert(u) {
+            continue;
+        }
+        for (v, w) in graph.adjacent(u) {
+            let next = cost + w;
+            if result.relax(u, v, next) {
+                heap.push(State {
+                    cost: next,
+                    node: v,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// Shortest paths using a [`BucketQueue`], for small integer weights.
+///
+/// Every shortest distance must be strictly less than `N`; longer tentative
+/// distances are skipped. Panics if some node is only reachable at distance `N` or more.
+pub fn dijkstra_bucket<G, const N: usize>(graph: &G, start: G::Node) -> Dijkstra<G::Node, usize>
+where
+    G: Graph<Weight = usize>,
+{
+    let mut queue = BucketQueue::<G::Node, N>::create(HashMap::from([(start, 0)]));
+    let mut result = Dijkstra::new(start);
+    // edges whose tentative distance did not fit; a shorter path may still reach their targets
+    let mut overflowed = Vec::new();
+
+    while let Some(popped) = queue.pop_min() {
+        let u = popped.value;
+        for (v, w) in graph.adjacent(u) {
+            let next = popped.priority + w;
+            if next >= N {
+                overflowed.push((v, next));
+            } else if result.relax(u, v, next) {
+                queue.push(v, next);
+            }
+        }
+    }
+
+    if let Some((_, next)) = overflowed
+        .into_iter()
+        .find(|(v, _)| !result.distance.contains_key(v))
+    {
+        panic!("distance {next} does not fit in the bucket queue");
+    }
+    result
+}
+
+/// Dial's algorithm: shortest paths using a [`MonotoneBucketQueue`], for small integer
+/// weights with no bound on the number of nodes or the total distance
+pub fn dijkstra_dial<G>(graph: &G, start: G::Node) -> Dijkstra<G::Node, usize>
+where
+    G: Graph<Weight = usize>,
+{
+    dijkstra_with_queue(graph, start, MonotoneBucketQueue::new())
+}
+
+/// Shortest paths driven by any integer priority queue, such as a [`RadixHeap`](crate::bucket::RadixHeap) when
+/// distances are too large for buckets
+pub fn dijkstra_with_queue<G, Q>(
+    graph: &G,
+    start: G::Node,
+    mut queue: Q,
+) -> Dijkstra<G::Node, usize>
+where
+    G: Graph<Weight = usize>,
+    Q: PriorityQueue<G::Node>,
+{
+    queue.push(start, 0);
+    let mut result = Dijkstra::new(start);
+
+    while let Some(popped) = queue.pop_min() {
+        let u = popped.value;
+        for (v, w) in graph.adjacent(u) {
+            let next = popped.priority + w;
+            if result.relax(u, v, next) {
+                queue.push(v, next);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::point2;
+
+    use super::*;
+    use crate::{
+        bucket::RadixHeap,
+        graph::{from_fn, from_grid},
+        grid::{Grid, Point, UP_RIGHT_DOWN_LEFT},
+    };
+
+    #[test]
+    fn test_adjacency_list() {
+        let g = HashMap::from([
+            ('a', vec![('b', 7), ('c', 9), ('f', 14)]),
+            ('b', vec![('a', 7), ('c', 10), ('d', 15)]),
+            ('c', vec![('a', 9), ('b', 10), ('d', 11), ('f', 2)]),
+            ('d', vec![('b', 15), ('c', 11), ('e', 6)]),
+            ('e', vec![('d', 6), ('f', 9)]),
+            ('f', vec![('a', 14), ('c', 2), ('e', 9)]),
+        ]);
+        let result = dijkstra(&g, 'a');
+        assert_eq!(result.distance[&'e'], 20);
+        assert_eq!(result.distance[&'d'], 20);
+        assert_eq!(result.path_to('e').unwrap(), ['a', 'c', 'f', 'e']);
+
+        let bucket = dijkstra_bucket::<_, 64>(&g, 'a');
+        assert_eq!(bucket.distance, result.distance);
+
+        let dial = dijkstra_dial(&g, 'a');
+        assert_eq!(dial.distance, result.distance);
+
+        let scaled: HashMap<char, Vec<(char, usize)>> = g
+            .iter()
+            .map(|(&k, v)| (k, v.iter().map(|&(n, w)| (n, w * 1_000_000_000)).collect()))
+            .collect();
+        let radix = dijkstra_with_queue(&scaled, 'a', RadixHeap::new());
+        assert_eq!(radix.distance[&'e'], 20_000_000_000);
+    }
+
+    /// Moving costs 1 and turning costs 1000, as in a reindeer maze
+    #[test]
+    fn test_all_best_paths() {
+        let g = Grid::read(
+            "\
+#######
+#....E#
+#.#.#.#
+#S....#
+#######
+",
+            |x| x,
+        );
+        let start = g.position(|&c| c == 'S').unwrap();
+        let end = g.position(|&c| c == 'E').unwrap();
+
+        let maze = from_fn(|(p, d): (Point, usize)| {
+            let forward = p + UP_RIGHT_DOWN_LEFT[d];
+            let step = (g[forward] != '#').then_some(((forward, d), 1));
+            [((p, (d + 1) % 4), 1000), ((p, (d + 3) % 4), 1000)]
+                .into_iter()
+                .chain(step)
+        });
+
+        let result = dijkstra(&maze, (start, 1));
+        let ends = (0..4).map(|d| (end, d));
+        let best = ends.clone().filter_map(|e| result.distance.get(&e)).min();
+        assert_eq!(best, Some(&1006));
+
+        let tiles: HashSet<Point> = result
+            .on_shortest_paths(ends)
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(tiles.len(), 7);
+        assert!(tiles.contains(&point2(5, 2)));
+        assert!(!tiles.contains(&point2(3, 2)));
+    }
+
+    #[test]
+    fn test_tied_paths() {
+        let g = Grid::read("...\n.#.\n...\n...\n", |x| x);
+        let open = from_grid(&g, |&c| c != '#');
+        let result = dijkstra(&open, point2(0, 0));
+        assert_eq!(result.predecessors[&point2(2, 2)].len(), 2);
+        assert_eq!(result.on_shortest_paths([point2(2, 2)]).len(), 8);
+    }
+
+    #[test]
+    fn test_bucket_grid() {
+        let g = Grid::read("191\n111\n", |c| c.to_digit(10).unwrap() as usize);
+        let weighted = from_fn(|p| g.adjacent(p).into_iter().map(|(q, &w)| (q, w)));
+        let result = dijkstra_bucket::<_, 32>(&weighted, point2(0, 0));
+        assert_eq!(result.distance[&point2(2, 0)], 4);
+        assert_eq!(result.predecessors[&point2(2, 0)], [point2(2, 1)]);
+        assert_eq!(result.distance, dijkstra(&weighted, point2(0, 0)).distance);
+    }
+
+    #[test]
+    fn test_bucket_long_tentative_edge() {
+        let g = HashMap::from([('s', vec![('a', 100), ('b', 1)]), ('b', vec![('a', 1)])]);
+        let result = dijkstra_bucket::<_, 64>(&g, 's');
+        assert_eq!(result.distance[&'a'], 2);
+        assert_eq!(result.predecessors[&'a'], ['b']);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_bucket_overflow() {
+        let g = HashMap::from([('s', vec![('a', 100)])]);
+        dijkstra_bucket::<_, 64>(&g, 's');
+    }
+
+    #[test]
+    fn test_dial_large_grid() {
+        let g = Grid::new_with_dimensions(lina::vec2(300, 300), |p| {
+            (p.x * 7 + p.y * 13) as usize % 9 + 1
+        });
+        let weighted = from_fn(|p| g.adjacent(p).into_iter().map(|(q, &w)| (q, w)));
+        let corner = point2(299, 299);
+
+        let dial = dijkstra_dial(&weighted, point2(0, 0));
+        assert_eq!(dial.distance.len(), 300 * 300);
+        assert_eq!(
+            dial.distance[&corner],
+            dijkstra(&weighted, point2(0, 0)).distance[&corner]
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Graphs and grids shared by the graph algorithm tests
+
+use std::collections::HashMap;
+
+use crate::{graph::Node, grid::Grid};
+
+/// An adjacency list with each weighted edge listed from both ends
+pub fn undirected<N: Node, W: Copy>(edges: &[(N, N, W)]) -> HashMap<N, Vec<(N, W)>> {
+    let mut g: HashMap<N, Vec<(N, W)>> = HashMap::new();
+    for &(u, v, w) in edges {
+        g.entry(u).or_default().push((v, w));
+        g.entry(v).or_default().push((u, w));
+    }
+    g
+}
+
+/// A short maze from `S` to `E` with a walled-off cell at (2, 0)
+pub fn small_maze() -> Grid<char> {
+    Grid::read(
+        "\
+S.#...
+.##.#.
+....#E
+",
+        |x| x,
+    )
+}
+
+/// An open border around walled rooms, so straight-line guesses are often wrong
+pub fn walled_maze() -> Grid<char> {
+    Grid::read(
+        "\
+..........
+.########.
+.#......#.
+.#.####.#.
+...#..#...
+.#.#..###.
+.#........
+",
+        |x| x,
+    )
+}
+
+/// Corridors between three junctions, one of them through an eastward slope `>`
+pub fn slope_maze() -> Grid<char> {
+    Grid::read(
+        "\
+#S#####
+#.....#
+#.#.#.#
+#...>.#
+###.###
+###E###
+",
+        |x| x,
+    )
+}
@@ -0,0 +1,430 @@
+use std::{
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    ops::Sub,
+};
+
+use num::{Bounded, Zero};
+
+use crate::graph::{Graph, Node, Weight};
+
+pub trait Capacity: Weight + Sub<Output = Self> + Bounded {}
+impl<T: Weight + Sub<Output = T> + Bounded> Capacity for T {}
+
+struct Edge<C> {
+    to: usize,
+    // remaining capacity in the residual graph
+    residual: C,
+    capacity: C,
+}
+
+/// A flow network over integer capacities, solved with Dinic's algorithm
+pub struct FlowNetwork<N: Node, C: Capacity> {
+    index: HashMap<N, usize>,
+    nodes: Vec<N>,
+    // edge `e` and its residual partner are stored at `e` and `e ^ 1`
+    edges: Vec<Edge<C>>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+/// A minimum cut separating a source from a sink
+pub struct MinCut<N: Node, C> {
+    pub value: C,
+    /// Nodes still reachable from the source in the residual graph
+    pub source_side: HashSet<N>,
+    /// Saturated edges from the source side to the sink side
+    pub edges: Vec<(N, N)>,
+}
+
+impl<N: Node, C: Capacity> Default for FlowNetwork<N, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Node, C: Capacity> FlowNetwork<N, C> {
+    pub fn new() -> Self {
+        FlowNetwork {
+            index: HashMap::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            adjacency: Vec::new(),
+        }
+    }
+
+    /// Every edge of `graph` reachable from `nodes`, with its weight as capacity
+    pub fn from_graph<G>(graph: &G, nodes: impl IntoIterator<Item = N>) -> Self
+    where
+        G: Graph<Node = N, Weight = C>,
+    {
+        let mut network = Self::new();
+        let mut stack: Vec<N> = nodes.into_iter().collect();
+        let mut seen: HashSet<N> = stack.iter().copied().collect();
+        while let Some(u) = stack.pop() {
+            for (v, c) in graph.adjacent(u) {
+                network.add_edge(u, v, c);
+                if seen.insert(v) {
+                    stack.push(v);
+                }
+            }
+        }
+        network
+    }
+
+    fn node(&mut self, n: N) -> usize {
+        *self.index.entry(n).or_insert_with(|| {
+            self.nodes.push(n);
+            self.adjacency.push(Vec::new());
+            self.nodes.len() - 1
+        })
+    }
+
+    fn push_edge(&mut self, u: usize, v: usize, forward: C, backward: C) {
+        self.adjacency[u].push(self.edges.len());
+        self.edges.push(Edge {
+            to: v,
+            residual: forward,
+            capacity: forward,
+        });
+        self.adjacency[v].push(self.edges.len());
+        self.edges.push(Edge {
+            to: u,
+            residual: backward,
+            capacity: backward,
+        });
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, capacity: C) {
+        let (u, v) = (self.node(from), self.node(to));
+        self.push_edge(u, v, capacity, C::zero());
+    }
+
+    /// An edge that can carry `capacity` in either direction
+    pub fn add_undirected_edge(&mut self, a: N, b: N, capacity: C) {
+        let (u, v) = (self.node(a), self.node(b));
+        self.push_edge(u, v, capacity, capacity);
+    }
+
+    fn levels(&self, s: usize) -> Vec<Option<usize>> {
+        let mut level = vec![None; self.nodes.len()];
+        level[s] = Some(0);
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            for &e in &self.adjacency[u] {
+                let edge = &self.edges[e];
+                if edge.residual > C::zero() && level[edge.to].is_none() {
+                    level[edge.to] = level[u].map(|l| l + 1);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        level
+    }
+
+    /// Find one augmenting path along increasing levels and push flow through it,
+    /// walking with an explicit path so long networks do not overflow the stack
+    fn augment(&mut self, s: usize, t: usize, level: &[Option<usize>], next: &mut [usize]) -> C {
+        let mut path: Vec<usize> = Vec::new();
+        let mut u = s;
+        loop {
+            if u == t {
+                let pushed = path.iter().fold(C::max_value(), |limit, &e| {
+                    limit.min(self.edges[e].residual)
+                });
+                for &e in &path {
+                    self.edges[e].residual = self.edges[e].residual - pushed;
+                    self.edges[e ^ 1].residual = self.edges[e ^ 1].residual + pushed;
+                }
+                return pushed;
+            }
+            let forward = self.adjacency[u][next[u]..].iter().position(|&e| {
+                let Edge { to, residual, .. } = self.edges[e];
+                residual > C::zero() && level[to] == level[u].map(|l| l + 1)
+            });
+            match forward {
+                Some(skipped) => {
+                    next[u] += skipped;
+                    let e = self.adjacency[u][next[u]];
+                    path.push(e);
+                    u = self.edges[e].to;
+                }
+                None => {
+                    // dead end: retreat and never try this node's edges again in this phase
+                    next[u] = self.adjacency[u].len();
+                    let Some(e) = path.pop() else {
+                        return C::zero();
+                    };
+                    u = self.edges[e ^ 1].to;
+                    next[u] += 1;
+                }
+            }
+        }
+    }
+
+    /// Push as much flow as possible from `source` to `sink`, returning the amount added.
+    ///
+    /// The network keeps the flow, so a second call with the same pair returns zero.
+    pub fn max_flow(&mut self, source: N, sink: N) -> C {
+        let (s, t) = (self.node(source), self.node(sink));
+        let mut total = C::zero();
+        if s == t {
+            return total;
+        }
+        loop {
+            let level = self.levels(s);
+            if level[t].is_none() {
+                return total;
+            }
+            let mut next = vec![0; self.nodes.len()];
+            loop {
+                let pushed = self.augment(s, t, &level, &mut next);
+                if pushed == C::zero() {
+                    break;
+                }
+                total = total + pushed;
+            }
+        }
+    }
+
+    /// Restore every edge to its full capacity, discarding any flow pushed so far
+    pub fn reset(&mut self) {
+        for edge in &mut self.edges {
+            edge.residual = edge.capacity;
+        }
+    }
+
+    /// Saturate the network and read off a minimum `source`-`sink` cut.
+    ///
+    /// Flow already pushed by [`max_flow`](Self::max_flow) is kept, so call
+    /// [`reset`](Self::reset) first when switching to a different source or sink.
+    pub fn min_cut(&mut self, source: N, sink: N) -> MinCut<N, C> {
+        self.max_flow(source, sink);
+        let level = self.levels(self.index[&source]);
+        let source_side = (0..self.nodes.len())
+            .filter(|&u| level[u].is_some())
+            .map(|u| self.nodes[u])
+            .collect();
+        let cut: Vec<(usize, usize)> = (0..self.nodes.len())
+            .filter(|&u| level[u].is_some())
+            .flat_map(|u| self.adjacency[u].iter().map(move |&e| (u, e)))
+            .filter(|&(_, e)| {
+                let edge = &self.edges[e];
+                edge.capacity > C::zero() && level[edge.to].is_none()
+            })
+            .collect();
+        let value = cut
+            .iter()
+            .fold(C::zero(), |total, &(_, e)| total + self.edges[e].capacity);
+        let edges = cut
+            .into_iter()
+            .map(|(u, e)| (self.nodes[u], self.nodes[self.edges[e].to]))
+            .collect();
+        MinCut {
+            value,
+            source_side,
+            edges,
+        }
+    }
+}
+
+/// Stoer–Wagner minimum cut of an undirected graph, over every node reachable from
+/// `nodes`. Returns the cut weight and the nodes on one side of it, or `None` if
+/// there are fewer than two nodes.
+///
+/// Each undirected edge must be listed from both ends with the same weight.
+pub fn global_min_cut<G: Graph>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> Option<(G::Weight, Vec<G::Node>)> {
+    let mut index: HashMap<G::Node, usize> = HashMap::new();
+    let mut members: Vec<Vec<G::Node>> = Vec::new();
+    let mut stack: Vec<G::Node> = nodes.into_iter().collect();
+    let mut edges = Vec::new();
+    while let Some(u) = stack.pop() {
+        if index.contains_key(&u) {
+            continue;
+        }
+        index.insert(u, members.len());
+        members.push(vec![u]);
+        for (v, w) in graph.adjacent(u) {
+            edges.push((u, v, w));
+            if !index.contains_key(&v) {
+                stack.push(v);
+            }
+        }
+    }
+    let n = members.len();
+    let mut adjacency: Vec<HashMap<usize, G::Weight>> = vec![HashMap::new(); n];
+    for (u, v, w) in edges {
+        let (u, v) = (index[&u], index[&v]);
+        if u != v {
+            let entry = adjacency[u].entry(v).or_insert_with(G::Weight::zero);
+            *entry = *entry + w;
+        }
+    }
+
+    let mut active: Vec<usize> = (0..n).collect();
+    let mut best: Option<(G::Weight, Vec<G::Node>)> = None;
+    while active.len() > 1 {
+        // maximum adjacency ordering
+        let mut connectivity: HashMap<usize, G::Weight> =
+            active.iter().map(|&u| (u, G::Weight::zero())).collect();
+        let mut heap: BinaryHeap<(G::Weight, usize)> =
+            active.iter().map(|&u| (G::Weight::zero(), u)).collect();
+        let mut added = HashSet::new();
+        let (mut previous, mut last) = (active[0], active[0]);
+        while let Some((w, u)) = heap.pop() {
+            if added.contains(&u) || connectivity[&u] != w {
+                continue;
+            }
+            added.insert(u);
+            (previous, last) = (last, u);
+            for (&v, &c) in &adjacency[u] {
+                if !added.contains(&v) {
+                    let total = connectivity[&v] + c;
+                    connectivity.insert(v, total);
+                    heap.push((total, v));
+                }
+            }
+        }
+
+        let cut = connectivity[&last];
+        if best.as_ref().is_none_or(|(b, _)| cut < *b) {
+            best = Some((cut, members[last].clone()));
+        }
+
+        // merge the last node into the one added before it
+        let merged = std::mem::take(&mut adjacency[last]);
+        for (v, c) in merged {
+            adjacency[v].remove(&last);
+            if v != previous {
+                let entry = adjacency[previous].entry(v).or_insert_with(G::Weight::zero);
+                *entry = *entry + c;
+                let entry = adjacency[v].entry(previous).or_insert_with(G::Weight::zero);
+                *entry = *entry + c;
+            }
+        }
+        let moved = std::mem::take(&mut members[last]);
+        members[previous].extend(moved);
+        active.retain(|&u| u != last);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_flow() {
+        let mut network = FlowNetwork::new();
+        for (u, v, c) in [
+            ('s', 'a', 10),
+            ('s', 'c', 10),
+            ('a', 'b', 4),
+            ('a', 'c', 2),
+            ('a', 'd', 8),
+            ('c', 'd', 9),
+            ('b', 't', 10),
+            ('d', 'b', 6),
+            ('d', 't', 10),
+        ] {
+            network.add_edge(u, v, c);
+        }
+        let cut = network.min_cut('s', 't');
+        assert_eq!(cut.value, 19u32);
+        assert_eq!(cut.source_side, HashSet::from(['s', 'c']));
+
+        let mut edges = cut.edges;
+        edges.sort();
+        assert_eq!(edges, [('c', 'd'), ('s', 'a')]);
+    }
+
+    #[test]
+    fn test_from_graph() {
+        let g: HashMap<u8, Vec<(u8, u64)>> = HashMap::from([
+            (0, vec![(1, 3), (2, 2)]),
+            (1, vec![(3, 2)]),
+            (2, vec![(3, 3)]),
+        ]);
+        let mut network = FlowNetwork::from_graph(&g, [0]);
+        assert_eq!(network.max_flow(0, 3), 4);
+        assert_eq!(network.max_flow(0, 3), 0);
+    }
+
+    #[test]
+    fn test_cut_after_flow() {
+        let mut network = FlowNetwork::new();
+        network.add_edge('s', 'a', 3);
+        network.add_edge('a', 't', 2);
+        network.add_edge('a', 'u', 5);
+        assert_eq!(network.max_flow('s', 't'), 2);
+
+        let cut = network.min_cut('s', 't');
+        assert_eq!(cut.value, 2u32);
+        assert_eq!(cut.edges, [('a', 't')]);
+
+        network.reset();
+        let cut = network.min_cut('s', 'u');
+        assert_eq!(cut.value, 3);
+        assert_eq!(cut.edges, [('s', 'a')]);
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let mut network = FlowNetwork::new();
+        for n in 0..100_000u32 {
+            network.add_edge(n, n + 1, 1 + n % 3);
+        }
+        assert_eq!(network.max_flow(0, 100_000), 1);
+    }
+
+    /// Three wires joining two clusters of five
+    fn wires() -> HashMap<char, Vec<(char, usize)>> {
+        let mut g: HashMap<_, Vec<_>> = HashMap::new();
+        let mut connect = |u, v| {
+            g.entry(u).or_default().push((v, 1));
+            g.entry(v).or_default().push((u, 1));
+        };
+        for cluster in ["abcde", "vwxyz"] {
+            for (i, u) in cluster.chars().enumerate() {
+                for v in cluster.chars().skip(i + 1) {
+                    connect(u, v);
+                }
+            }
+        }
+        connect('a', 'w');
+        connect('b', 'x');
+        connect('c', 'y');
+        g
+    }
+
+    #[test]
+    fn test_undirected_cut() {
+        let g = wires();
+        let mut network = FlowNetwork::new();
+        for (&u, adjacent) in &g {
+            for &(v, c) in adjacent {
+                if u < v {
+                    network.add_undirected_edge(u, v, c);
+                }
+            }
+        }
+        let cut = network.min_cut('a', 'z');
+        assert_eq!(cut.value, 3);
+        assert_eq!(cut.source_side, HashSet::from_iter("abcde".chars()));
+        assert_eq!(cut.edges.len(), 3);
+    }
+
+    #[test]
+    fn test_global_min_cut() {
+        let g = wires();
+        let (weight, mut side) = global_min_cut(&g, ['a']).unwrap();
+        assert_eq!(weight, 3);
+        side.sort();
+        let side: String = side.into_iter().collect();
+        assert!(side == "abcde" || side == "vwxyz");
+
+        let single: HashMap<u8, Vec<(u8, usize)>> = HashMap::new();
+        assert!(global_min_cut(&single, [0]).is_none());
+    }
+}
@@ -0,0 +1,232 @@
+use std::thread;
+
+use num::Zero;
+
+use crate::graph::{Graph, Weight};
+
+type Best<W> = Option<(W, Vec<usize>)>;
+
+struct Search<W> {
+    adjacency: Vec<Vec<(usize, W)>>,
+    adjacency_mask: Vec<u64>,
+    // heaviest edge into each node, the most it can add to any path
+    max_incoming: Vec<W>,
+    end: usize,
+}
+
+impl<W: Weight> Search<W> {
+    fn new<G: Graph<Node = usize, Weight = W>>(graph: &G, nodes: usize, end: usize) -> Self {
+        assert!(
+            nodes <= 64,
+            "bitmask search supports at most 64 nodes, got {nodes}"
+        );
+        let adjacency: Vec<Vec<(usize, W)>> = (0..nodes).map(|u| graph.adjacent(u)).collect();
+        let mut max_incoming = vec![W::zero(); nodes];
+        let mut adjacency_mask = vec![0; nodes];
+        for (u, edges) in adjacency.iter().enumerate() {
+            for &(v, w) in edges {
+                adjacency_mask[u] |= 1 << v;
+                max_incoming[v] = max_incoming[v].max(w);
+            }
+        }
+        Search {
+            adjacency,
+            adjacency_mask,
+            max_incoming,
+            end,
+        }
+    }
+
+    /// Nodes reachable from `u` without passing through `visited`
+    fn reachable(&self, u: usize, visited: u64) -> u64 {
+        let mut reach = 0;
+        let mut frontier = self.adjacency_mask[u] & !visited;
+        while frontier != 0 {
+            reach |= frontier;
+            let mut next = 0;
+            let mut bits = frontier;
+            while bits != 0 {
+                next |= self.adjacency_mask[bits.trailing_zeros() as usize];
+                bits &= bits - 1;
+            }
+            frontier = next & !visited & !reach;
+        }
+        reach
+    }
+
+    fn upper_bound(&self, reach: u64) -> W {
+        let mut bound = W::zero();
+        let mut bits = reach;
+        while bits != 0 {
+            bound = bound + self.max_incoming[bits.trailing_zeros() as usize];
+            bits &= bits - 1;
+        }
+        bound
+    }
+
+    fn dfs(&self, u: usize, visited: u64, length: W, path: &mut Vec<usize>, best: &mut Best<W>) {
+        if u == self.end {
+            if best.as_ref().is_none_or(|(b, _)| length > *b) {
+                *best = Some((length, path.clone()));
+            }
+            return;
+        }
+        let reach = self.reachable(u, visited);
+        if reach & (1 << self.end) == 0 {
+            return;
+        }
+        if let Some((b, _)) = best
+            && length + self.upper_bound(reach) <= *b
+        {
+            return;
+        }
+        for &(v, w) in &self.adjacency[u] {
+            if visited & (1 << v) == 0 {
+                path.push(v);
+                self.dfs(v, visited | (1 << v), length + w, path, best);
+                path.pop();
+            }
+        }
+    }
+}
+
+/// The longest simple path from `start` to `end` over nodes `0..nodes`, at most 64 of them
+pub fn longest_path<G>(graph: &G, nodes: usize, start: usize, end: usize) -> Best<G::Weight>
+where
+    G: Graph<Node = usize>,
+{
+    let search = Search::new(graph, nodes, end);
+    let mut best = None;
+    search.dfs(
+        start,
+        1 << start,
+        G::Weight::zero(),
+        &mut vec![start],
+        &mut best,
+    );
+    best
+}
+
+/// [`longest_path`], splitting the first few levels of the search across threads
+pub fn longest_path_parallel<G>(
+    graph: &G,
+    nodes: usize,
+    start: usize,
+    end: usize,
+) -> Best<G::Weight>
+where
+    G: Graph<Node = usize>,
+    G::Weight: Send + Sync,
+{
+    let search = Search::new(graph, nodes, end);
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+    // expand breadth first until there is a prefix for every thread
+    let mut prefixes = vec![(vec![start], 1u64 << start, G::Weight::zero())];
+    let mut finished = None;
+    while prefixes.len() < threads {
+        let mut expanded = Vec::new();
+        for (path, visited, length) in &prefixes {
+            let u = path[path.len() - 1];
+            if u == end {
+                if finished.as_ref().is_none_or(|(b, _)| length > b) {
+                    finished = Some((*length, path.clone()));
+                }
+                continue;
+            }
+            for &(v, w) in &search.adjacency[u] {
+                if visited & (1 << v) == 0 {
+                    let mut path = path.clone();
+                    path.push(v);
+                    expanded.push((path, visited | (1 << v), *length + w));
+                }
+            }
+        }
+        if expanded.is_empty() {
+            break;
+        }
+        prefixes = expanded;
+    }
+
+    let search = &search;
+    let results: Vec<Best<G::Weight>> = thread::scope(|scope| {
+        let handles: Vec<_> = prefixes
+            .into_iter()
+            .map(|(mut path, visited, length)| {
+                scope.spawn(move || {
+                    let mut best = None;
+                    let u = path[path.len() - 1];
+                    search.dfs(u, visited, length, &mut path, &mut best);
+                    best
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("search thread panicked"))
+            .collect()
+    });
+    results
+        .into_iter()
+        .chain([finished])
+        .flatten()
+        .max_by_key(|(length, _)| *length)
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::point2;
+
+    use super::*;
+    use crate::{
+        graph::{compress::compress_corridors, fixtures::undirected},
+        grid::Grid,
+    };
+
+    #[test]
+    fn test_longest_path() {
+        let g = undirected(&[
+            (0, 1, 1),
+            (1, 2, 1),
+            (0, 3, 5),
+            (3, 2, 1),
+            (1, 3, 1),
+            (2, 4, 1),
+        ]);
+        let (length, path) = longest_path(&g, 5, 0, 4).unwrap();
+        assert_eq!(length, 8);
+        assert_eq!(path, [0, 3, 1, 2, 4]);
+
+        assert_eq!(longest_path_parallel(&g, 5, 0, 4).unwrap().0, 8);
+        assert!(longest_path(&g, 6, 0, 5).is_none());
+    }
+
+    #[test]
+    fn test_compressed_maze() {
+        let g = Grid::read(
+            "\
+#.#####
+#.....#
+#.#.#.#
+#.....#
+#.#.#.#
+#.....#
+#####.#
+",
+            |x| x,
+        );
+        let (start, end) = (point2(1, 0), point2(5, 6));
+        let compressed = compress_corridors(&g, |&c| c != '#', [start, end]);
+        let (s, e) = (compressed.index[&start], compressed.index[&end]);
+
+        let (length, path) = longest_path(&compressed, compressed.len(), s, e).unwrap();
+        assert_eq!(length, 18);
+        assert_eq!((path[0], path[path.len() - 1]), (s, e));
+        assert_eq!(
+            longest_path_parallel(&compressed, compressed.len(), s, e)
+                .unwrap()
+                .0,
+            length
+        );
+    }
+}
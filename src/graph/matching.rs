@@ -0,0 +1,254 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::graph::Graph;
+
+struct HopcroftKarp {
+    adjacency: Vec<Vec<usize>>,
+    pair_left: Vec<Option<usize>>,
+    pair_right: Vec<Option<usize>>,
+    layer: Vec<usize>,
+}
+
+impl HopcroftKarp {
+    /// Layer the free left nodes and their alternating paths; true if an augmenting path exists
+    fn layers(&mut self) -> bool {
+        let mut queue = VecDeque::new();
+        for u in 0..self.adjacency.len() {
+            if self.pair_left[u].is_none() {
+                self.layer[u] = 0;
+                queue.push_back(u);
+            } else {
+                self.layer[u] = usize::MAX;
+            }
+        }
+        let mut found = false;
+        while let Some(u) = queue.pop_front() {
+            for &v in &self.adjacency[u] {
+                match self.pair_right[v] {
+                    None => found = true,
+                    Some(w) if self.layer[w] == usize::MAX => {
+                        self.layer[w] = self.layer[u] + 1;
+                        queue.push_back(w);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        found
+    }
+
+    fn augment(&mut self, u: usize) -> bool {
+        for i in 0..self.adjacency[u].len() {
+            let v = self.adjacency[u][i];
+            let free = match self.pair_right[v] {
+                None => true,
+                Some(w) => self.layer[w] == self.layer[u] + 1 && self.augment(w),
+            };
+            if free {
+                self.pair_left[u] = Some(v);
+                self.pair_right[v] = Some(u);
+                return true;
+            }
+        }
+        self.layer[u] = usize::MAX;
+        false
+    }
+}
+
+/// Hopcroft–Karp maximum matching between `left` and the nodes adjacent to them
+pub fn maximum_matching<G: Graph>(
+    graph: &G,
+    left: impl IntoIterator<Item = G::Node>,
+) -> HashMap<G::Node, G::Node> {
+    let left: Vec<G::Node> = left.into_iter().collect();
+    let mut right_index: HashMap<G::Node, usize> = HashMap::new();
+    let mut right: Vec<G::Node> = Vec::new();
+    let adjacency = left
+        .iter()
+        .map(|&u| {
+            graph
+                .successors(u)
+                .into_iter()
+                .map(|v| {
+                    *right_index.entry(v).or_insert_with(|| {
+                        right.push(v);
+                        right.len() - 1
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut hk = HopcroftKarp {
+        adjacency,
+        pair_left: vec![None; left.len()],
+        pair_right: vec![None; right.len()],
+        layer: vec![0; left.len()],
+    };
+    while hk.layers() {
+        for u in 0..left.len() {
+            if hk.pair_left[u].is_none() {
+                hk.augment(u);
+            }
+        }
+    }
+
+    (0..left.len())
+        .filter_map(|u| hk.pair_left[u].map(|v| (left[u], right[v])))
+        .collect()
+}
+
+/// Solve an assignment by repeatedly fixing a left node with exactly one remaining
+/// candidate and removing that candidate from every other node.
+///
+/// Returns `None` if the elimination gets stuck or a node runs out of candidates.
+pub fn assign_by_elimination<G: Graph>(
+    graph: &G,
+    left: impl IntoIterator<Item = G::Node>,
+) -> Option<HashMap<G::Node, G::Node>> {
+    let mut candidates: HashMap<G::Node, HashSet<G::Node>> = left
+        .into_iter()
+        .map(|u| (u, graph.successors(u).into_iter().collect()))
+        .collect();
+    let mut assignment = HashMap::new();
+    while !candidates.is_empty() {
+        let (&u, only) = candidates.iter().find(|(_, c)| c.len() <= 1)?;
+        let &v = only.iter().next()?;
+        candidates.remove(&u);
+        for c in candidates.values_mut() {
+            c.remove(&v);
+        }
+        assignment.insert(u, v);
+    }
+    Some(assignment)
+}
+
+/// Hungarian algorithm for the minimum cost assignment of every row of `cost` to a
+/// distinct column. Returns the total cost and the column chosen for each row.
+///
+/// Panics if there are more rows than columns.
+pub fn min_cost_assignment(cost: &[Vec<i64>]) -> (i64, Vec<usize>) {
+    let n = cost.len();
+    let m = cost.first().map_or(0, |r| r.len());
+    assert!(n <= m, "cannot assign {n} rows to {m} columns");
+
+    // 1-indexed potentials, with column 0 as a virtual start
+    let mut u = vec![0; n + 1];
+    let mut v = vec![0; m + 1];
+    let mut row_of = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+    for i in 1..=n {
+        row_of[0] = i;
+        let mut j0 = 0;
+        let mut min_to = vec![i64::MAX; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = i64::MAX;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let reduced = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                if reduced < min_to[j] {
+                    min_to[j] = reduced;
+                    way[j] = j0;
+                }
+                if min_to[j] < delta {
+                    delta = min_to[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_to[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if row_of[j0] == 0 {
+                break;
+            }
+        }
+        while j0 != 0 {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for j in 1..=m {
+        if row_of[j] != 0 {
+            assignment[row_of[j] - 1] = j - 1;
+        }
+    }
+    let total = assignment
+        .iter()
+        .enumerate()
+        .map(|(i, &j)| cost[i][j])
+        .sum();
+    (total, assignment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(
+        pairs: &[(&'static str, &[&'static str])],
+    ) -> HashMap<&'static str, Vec<(&'static str, usize)>> {
+        pairs
+            .iter()
+            .map(|&(k, vs)| (k, vs.iter().map(|&v| (v, 1)).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_maximum_matching() {
+        let g = candidates(&[
+            ("a", &["1", "2"]),
+            ("b", &["1"]),
+            ("c", &["2", "3"]),
+            ("d", &["3"]),
+            ("e", &["3"]),
+        ]);
+        let matching = maximum_matching(&g, ["a", "b", "c", "d", "e"]);
+        assert_eq!(matching.len(), 3);
+        assert!(matching.iter().all(|(u, v)| g[u].contains(&(v, 1))));
+        let used: HashSet<&str> = matching.values().copied().collect();
+        assert_eq!(used.len(), 3);
+    }
+
+    #[test]
+    fn test_elimination() {
+        let g = candidates(&[
+            ("row", &["1", "2"]),
+            ("class", &["2"]),
+            ("seat", &["1", "2", "3"]),
+        ]);
+        let assignment = assign_by_elimination(&g, ["row", "class", "seat"]).unwrap();
+        assert_eq!(
+            assignment,
+            HashMap::from([("class", "2"), ("row", "1"), ("seat", "3")])
+        );
+
+        let ambiguous = candidates(&[("x", &["1", "2"]), ("y", &["1", "2"])]);
+        assert!(assign_by_elimination(&ambiguous, ["x", "y"]).is_none());
+    }
+
+    #[test]
+    fn test_min_cost_assignment() {
+        let cost = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        assert_eq!(min_cost_assignment(&cost), (5, vec![1, 0, 2]));
+
+        let wide = vec![vec![7, 3, 9, 1], vec![2, 8, 4, 1]];
+        assert_eq!(min_cost_assignment(&wide), (3, vec![3, 0]));
+
+        assert_eq!(min_cost_assignment(&[]), (0, vec![]));
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::graph::{Graph, Node};
+
+/// The strongly connected components of a directed graph
+pub struct Components<N: Node> {
+    /// The component id of every visited node
+    pub component: HashMap<N, usize>,
+    /// The members of each component, indexed by id. Ids are in topological order:
+    /// edges between components only go from lower to higher ids.
+    pub members: Vec<Vec<N>>,
+}
+
+impl<N: Node> Components<N> {
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// The DAG of components, with an unweighted edge wherever some node of one
+    /// component has an edge to a node of another
+    pub fn condensation<G: Graph<Node = N>>(
+        &self,
+        graph: &G,
+    ) -> HashMap<usize, Vec<(usize, usize)>> {
+        let mut dag: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+        for (id, members) in self.members.iter().enumerate() {
+            let mut targets: Vec<usize> = members
+                .iter()
+                .flat_map(|&u| graph.successors(u))
+                .filter_map(|v| self.component.get(&v).copied())
+                .filter(|&c| c != id)
+                .collect::<HashSet<usize>>()
+                .into_iter()
+                .collect();
+            targets.sort_unstable();
+            dag.insert(id, targets.into_iter().map(|c| (c, 1)).collect());
+        }
+        dag
+    }
+}
+
+struct Tarjan<'g, G: Graph> {
+    graph: &'g G,
+    index: HashMap<G::Node, usize>,
+    lowlink: HashMap<G::Node, usize>,
+    on_stack: HashSet<G::Node>,
+    stack: Vec<G::Node>,
+    // explicit call stack of (node, its neighbours, next neighbour to visit)
+    calls: Vec<(G::Node, Vec<G::Node>, usize)>,
+    members: Vec<Vec<G::Node>>,
+}
+
+impl<G: Graph> Tarjan<'_, G> {
+    fn visit(&mut self, v: G::Node) {
+        let i = self.index.len();
+        self.index.insert(v, i);
+        self.lowlink.insert(v, i);
+        self.stack.push(v);
+        self.on_stack.insert(v);
+        self.calls.push((v, self.graph.successors(v), 0));
+    }
+
+    fn lower(&mut self, v: G::Node, to: usize) {
+        let low = self.lowlink[&v].min(to);
+        self.lowlink.insert(v, low);
+    }
+
+    fn run(&mut self, root: G::Node) {
+        self.visit(root);
+        while let Some((v, neighbours, next)) = self.calls.last_mut() {
+            let v = *v;
+            if let Some(&w) = neighbours.get(*next) {
+                *next += 1;
+                if !self.index.contains_key(&w) {
+                    self.visit(w);
+                } else if self.on_stack.contains(&w) {
+                    self.lower(v, self.index[&w]);
+                }
+                continue;
+            }
+
+            self.calls.pop();
+            if let Some(&(parent, _, _)) = self.calls.last() {
+                self.lower(parent, self.lowlink[&v]);
+            }
+            if self.lowlink[&v] == self.index[&v] {
+                let mut component = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack.remove(&w);
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                self.members.push(component);
+            }
+        }
+    }
+}
+
+/// Tarjan's algorithm over every node reachable from `nodes`
+pub fn strongly_connected_components<G: Graph>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> Components<G::Node> {
+    let mut tarjan = Tarjan {
+        graph,
+        index: HashMap::new(),
+        lowlink: HashMap::new(),
+        on_stack: HashSet::new(),
+        stack: Vec::new(),
+        calls: Vec::new(),
+        members: Vec::new(),
+    };
+    for root in nodes {
+        if !tarjan.index.contains_key(&root) {
+            tarjan.run(root);
+        }
+    }
+
+    // Tarjan finds sink components first
+    let mut members = tarjan.members;
+    members.reverse();
+    let component = members
+        .iter()
+        .enumerate()
+        .flat_map(|(id, m)| m.iter().map(move |&n| (n, id)))
+        .collect();
+    Components { component, members }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::from_neighbours;
+
+    fn graph() -> HashMap<u32, Vec<(u32, usize)>> {
+        [
+            (0, vec![1]),
+            (1, vec![2, 4, 5]),
+            (2, vec![3, 6]),
+            (3, vec![2, 7]),
+            (4, vec![0, 5]),
+            (5, vec![6]),
+            (6, vec![5]),
+            (7, vec![3, 6]),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k, v.into_iter().map(|n| (n, 1)).collect()))
+        .collect()
+    }
+
+    #[test]
+    fn test_components() {
+        let g = graph();
+        let result = strongly_connected_components(&g, 0..8);
+        assert_eq!(result.len(), 3);
+
+        let mut sets: Vec<Vec<u32>> = result
+            .members
+            .iter()
+            .map(|m| {
+                let mut m = m.clone();
+                m.sort();
+                m
+            })
+            .collect();
+        sets.sort();
+        assert_eq!(sets, [vec![0, 1, 4], vec![2, 3, 7], vec![5, 6]]);
+
+        assert_eq!(result.component[&0], result.component[&4]);
+        assert_ne!(result.component[&0], result.component[&2]);
+    }
+
+    #[test]
+    fn test_condensation_is_topological() {
+        let g = graph();
+        let result = strongly_connected_components(&g, 0..8);
+        let dag = result.condensation(&g);
+
+        let (a, b, c) = (
+            result.component[&0],
+            result.component[&2],
+            result.component[&5],
+        );
+        assert_eq!((a, b, c), (0, 1, 2));
+        assert_eq!(dag[&a], [(b, 1), (c, 1)]);
+        assert_eq!(dag[&b], [(c, 1)]);
+        assert!(dag[&c].is_empty());
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let chain = from_neighbours(|n: u32| (n < 100_000).then_some(n + 1));
+        let result = strongly_connected_components(&chain, [0]);
+        assert_eq!(result.len(), 100_001);
+        assert_eq!(result.component[&0], 0);
+    }
+}
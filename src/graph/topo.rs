@@ -0,0 +1,208 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::graph::Graph;
+
+/// Kahn's algorithm over `nodes`, breaking ties by taking the smallest ready node.
+///
+/// Only edges between the given nodes are considered. On failure the nodes of one
+/// cycle are returned in edge order.
+pub fn topological_sort<G>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> Result<Vec<G::Node>, Vec<G::Node>>
+where
+    G: Graph,
+    G::Node: Ord,
+{
+    topological_sort_by(graph, nodes, |a, b| a.cmp(b))
+}
+
+/// Kahn's algorithm over `nodes`, taking the ready node that is least according to `cmp`.
+///
+/// Only edges between the given nodes are considered. On failure the nodes of one
+/// cycle are returned in edge order.
+pub fn topological_sort_by<G: Graph>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+    cmp: impl Fn(&G::Node, &G::Node) -> Ordering,
+) -> Result<Vec<G::Node>, Vec<G::Node>> {
+    let mut nodes: Vec<G::Node> = nodes
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    nodes.sort_by(&cmp);
+    let rank: HashMap<G::Node, usize> = nodes.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+
+    let mut successors = vec![Vec::new(); nodes.len()];
+    let mut predecessors = vec![Vec::new(); nodes.len()];
+    let mut in_degree = vec![0; nodes.len()];
+    for (u, &n) in nodes.iter().enumerate() {
+        for v in graph.successors(n) {
+            if let Some(&v) = rank.get(&v) {
+                successors[u].push(v);
+                predecessors[v].push(u);
+                in_degree[v] += 1;
+            }
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..nodes.len())
+        .filter(|&u| in_degree[u] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(Reverse(u)) = ready.pop() {
+        order.push(nodes[u]);
+        for &v in &successors[u] {
+            in_degree[v] -= 1;
+            if in_degree[v] == 0 {
+                ready.push(Reverse(v));
+            }
+        }
+    }
+    if order.len() == nodes.len() {
+        return Ok(order);
+    }
+
+    // every unplaced node has an unplaced predecessor, so walking backwards must repeat
+    let mut seen = vec![None; nodes.len()];
+    let mut walk = Vec::new();
+    let mut u = (0..nodes.len())
+        .find(|&u| in_degree[u] > 0)
+        .expect("an unplaced node exists");
+    while seen[u].is_none() {
+        seen[u] = Some(walk.len());
+        walk.push(u);
+        u = *predecessors[u]
+            .iter()
+            .find(|&&p| in_degree[p] > 0)
+            .expect("an unplaced node has an unplaced predecessor");
+    }
+    let start = seen[u].expect("the walk repeated");
+    Err(walk[start..].iter().rev().map(|&u| nodes[u]).collect())
+}
+
+/// Whether no edge between two nodes of `sequence` points backwards
+pub fn is_topological_order<G: Graph>(graph: &G, sequence: &[G::Node]) -> bool {
+    let position: HashMap<G::Node, usize> =
+        sequence.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    sequence.iter().enumerate().all(|(i, &u)| {
+        graph
+            .successors(u)
+            .into_iter()
+            .all(|v| position.get(&v).is_none_or(|&j| j > i))
+    })
+}
+
+/// Reorder `sequence` to respect the edges between its nodes, moving as little as
+/// possible: ready nodes are taken in their original order
+pub fn fix_order<G: Graph>(graph: &G, sequence: &[G::Node]) -> Result<Vec<G::Node>, Vec<G::Node>> {
+    let position: HashMap<G::Node, usize> = sequence
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, &n)| (n, i))
+        .collect();
+    topological_sort_by(graph, sequence.iter().copied(), |a, b| {
+        position[a].cmp(&position[b])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+
+    /// Adjacency from a list of `before -> after` rules
+    fn rules<N: Node>(pairs: &[(N, N)]) -> HashMap<N, Vec<(N, usize)>> {
+        let mut g: HashMap<N, Vec<(N, usize)>> = HashMap::new();
+        for &(a, b) in pairs {
+            g.entry(a).or_default().push((b, 1));
+        }
+        g
+    }
+
+    fn print_queue() -> HashMap<u32, Vec<(u32, usize)>> {
+        rules(&[
+            (47, 53),
+            (97, 13),
+            (97, 61),
+            (97, 47),
+            (75, 29),
+            (61, 13),
+            (75, 53),
+            (29, 13),
+            (97, 29),
+            (53, 29),
+            (61, 53),
+            (97, 53),
+            (61, 29),
+            (47, 13),
+            (75, 47),
+            (97, 75),
+            (47, 61),
+            (75, 61),
+            (47, 29),
+            (75, 13),
+            (53, 13),
+        ])
+    }
+
+    #[test]
+    fn test_valid_and_fix() {
+        let g = print_queue();
+        assert!(is_topological_order(&g, &[75, 47, 61, 53, 29]));
+        assert!(is_topological_order(&g, &[75, 29, 13]));
+        assert!(!is_topological_order(&g, &[75, 97, 47, 61, 53]));
+        assert!(!is_topological_order(&g, &[97, 13, 75, 29, 47]));
+
+        assert_eq!(
+            fix_order(&g, &[75, 97, 47, 61, 53]),
+            Ok(vec![97, 75, 47, 61, 53])
+        );
+        assert_eq!(fix_order(&g, &[61, 13, 29]), Ok(vec![61, 29, 13]));
+        assert_eq!(
+            fix_order(&g, &[97, 13, 75, 29, 47]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+    }
+
+    #[test]
+    fn test_lexicographic() {
+        let g = rules(&[
+            ('C', 'A'),
+            ('C', 'F'),
+            ('A', 'B'),
+            ('A', 'D'),
+            ('B', 'E'),
+            ('D', 'E'),
+            ('F', 'E'),
+        ]);
+        let order: String = topological_sort(&g, "ABCDEF".chars())
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(order, "CABDFE");
+
+        let order: String = topological_sort_by(&g, "ABCDEF".chars(), |a, b| b.cmp(a))
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(order, "CFADBE");
+    }
+
+    #[test]
+    fn test_cycle() {
+        let g = rules(&[(1, 2), (2, 3), (3, 4), (4, 2), (0, 1)]);
+        let cycle = topological_sort(&g, 0..5).unwrap_err();
+        assert_eq!(cycle.len(), 3);
+        for (i, &u) in cycle.iter().enumerate() {
+            let v = cycle[(i + 1) % cycle.len()];
+            assert!(g[&u].contains(&(v, 1)));
+        }
+    }
+}
@@ -1,14 +1,18 @@
+pub mod lines;
+pub mod region;
 pub mod sparse;
 
 use std::{
     fmt::Display,
-    ops::{Index, IndexMut},
+    ops::{Deref, DerefMut, Index, IndexMut},
 };
 
 use arrayvec::ArrayVec;
 use lina::{Matrix, Point2, Vec2, point2, vec2};
 
-#[derive(Debug)]
+use crate::grid::region::Connectivity;
+
+#[derive(Debug, Clone)]
 pub struct Grid<C> {
     inner: Vec<C>,
     width: usize,
@@ -16,15 +20,14 @@ pub struct Grid<C> {
 
 impl<C> Grid<C> {
     fn idx(&self, y: usize, x: usize) -> usize {
-        return y * self.width + x;
+        y * self.width + x
     }
 }
 
 pub type Point = Point2<i32>;
 
-pub trait GridTrait:
-    Index<Point, Output = Self::Cell> + IndexMut<Point, Output = Self::Cell>
-{
+/// Read access to a grid of cells; see [`GridTraitMut`] for grids that can also be written
+pub trait GridTrait: Index<Point, Output = Self::Cell> {
     type Cell;
 
     fn position(&self, test: fn(&Self::Cell) -> bool) -> Option<Point>;
@@ -38,8 +41,22 @@ pub trait GridTrait:
     fn display(&self) -> String
     where
         Self::Cell: Display;
+
+    /// Every cell reachable from `start` through neighbouring cells for which `same` holds
+    fn flood_fill(
+        &self,
+        start: Point,
+        connectivity: Connectivity,
+        same: impl Fn(&Self::Cell, &Self::Cell) -> bool,
+    ) -> Vec<Point> {
+        region::flood_fill(self, start, connectivity, same)
+    }
 }
 
+/// A grid whose cells can also be modified
+pub trait GridTraitMut: GridTrait + IndexMut<Point> {}
+impl<G: GridTrait + IndexMut<Point> + ?Sized> GridTraitMut for G {}
+
 impl<C> GridTrait for Grid<C> {
     type Cell = C;
 
@@ -127,14 +144,13 @@ impl<C> Grid<C> {
         let g: Vec<C> = input
             .trim()
             .split('\n')
-            .map(|ln| ln.chars().into_iter().map(cell))
-            .flatten()
+            .flat_map(|ln| ln.chars().map(cell))
             .collect();
 
         #[cfg(debug_assertions)]
         {
             let line_lens: Vec<usize> = input.trim().split("\n").map(|x| x.len()).collect();
-            if line_lens.len() > 0 {
+            if !line_lens.is_empty() {
                 let lens_str = line_lens
                     .iter()
                     .map(|x| x.to_string())
@@ -151,7 +167,7 @@ impl<C> Grid<C> {
     }
 
     pub fn position(&self, test: fn(&C) -> bool) -> Option<Point> {
-        self.iter_coordinates().filter(|x| test(&self[*x])).next()
+        self.iter_coordinates().find(|x| test(&self[*x]))
     }
 
     pub fn contains(&self, coord: Point) -> bool {
@@ -166,7 +182,7 @@ impl<C> Grid<C> {
     }
 
     pub fn dimension(&self) -> Vec2<i32> {
-        if self.inner.len() == 0 {
+        if self.inner.is_empty() {
             vec2(0, 0)
         } else {
             vec2(self.width as i32, (self.inner.len() / self.width) as i32)
@@ -174,10 +190,10 @@ impl<C> Grid<C> {
     }
 
     pub fn map<T>(&self, f: impl Fn(&C) -> T) -> Grid<T> {
-        return Grid {
-            inner: self.inner.iter().map(|x| f(x)).collect(),
+        Grid {
+            inner: self.inner.iter().map(f).collect(),
             width: self.width,
-        };
+        }
     }
 
     pub fn adjacent(&self, src: Point) -> ArrayVec<(Point, &C), 4> {
@@ -285,6 +301,54 @@ impl<C: Copy> Grid<C> {
     }
 }
 
+impl<C: Clone> Grid<C> {
+    /// A new grid of `dimension` where each cell is copied from `source(p)` in this grid
+    fn remap(&self, dimension: Vec2<i32>, source: impl Fn(Point) -> Point) -> Grid<C> {
+        Grid {
+            inner: PointIterator::new(dimension)
+                .map(|p| self[source(p)].clone())
+                .collect(),
+            width: dimension.x.max(0) as usize,
+        }
+    }
+
+    pub fn rotate_cw(&self) -> Grid<C> {
+        let dim = self.dimension();
+        self.remap(vec2(dim.y, dim.x), |p| point2(p.y, dim.y - 1 - p.x))
+    }
+
+    pub fn rotate_ccw(&self) -> Grid<C> {
+        let dim = self.dimension();
+        self.remap(vec2(dim.y, dim.x), |p| point2(dim.x - 1 - p.y, p.x))
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(&self) -> Grid<C> {
+        let dim = self.dimension();
+        self.remap(dim, |p| point2(dim.x - 1 - p.x, p.y))
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical(&self) -> Grid<C> {
+        let dim = self.dimension();
+        self.remap(dim, |p| point2(p.x, dim.y - 1 - p.y))
+    }
+
+    /// Mirror along the main diagonal, swapping rows and columns
+    pub fn transpose(&self) -> Grid<C> {
+        let dim = self.dimension();
+        self.remap(vec2(dim.y, dim.x), |p| point2(p.y, p.x))
+    }
+
+    /// All 8 rotations and reflections: the four clockwise rotations of this grid,
+    /// then the four clockwise rotations of its horizontal mirror image
+    pub fn symmetries(&self) -> impl Iterator<Item = Grid<C>> {
+        let rotations =
+            |g: Grid<C>| std::iter::successors(Some(g), |g| Some(g.rotate_cw())).take(4);
+        rotations(self.clone()).chain(rotations(self.flip_horizontal()))
+    }
+}
+
 pub const UP_RIGHT_DOWN_LEFT: [Vec2<i32>; 4] = [vec2(0, -1), vec2(1, 0), vec2(0, 1), vec2(-1, 0)];
 pub const NEIGHBOURS: [Vec2<i32>; 8] = [
     vec2(-1, -1),
@@ -303,7 +367,7 @@ pub fn orthogonal_to_index(dir: Vec2<i32>) -> Option<usize> {
         return None;
     }
     // x is ±1 XOR y is ±1
-    if !((x.abs() == 1) != (y.abs() == 1)) {
+    if (x.abs() == 1) == (y.abs() == 1) {
         return None;
     }
 
@@ -316,7 +380,7 @@ pub fn orthogonal_to_index(dir: Vec2<i32>) -> Option<usize> {
      * 0 | 1 |         2 |  0  |   2
      * -1| 0 |         2 |  1  |   3
      */
-    assert!(0 <= i && i < 4);
+    assert!((0..4).contains(&i));
     Some(i as usize)
 }
 
@@ -326,12 +390,20 @@ impl<C: PartialEq> PartialEq for Grid<C> {
     }
 }
 
-pub struct TransformGrid<'a, C> {
+/// A rotated and/or reflected view of a [`Grid`], presenting transformed coordinates
+/// in every method. `R` is the borrow of the underlying grid.
+pub struct Transformed<R> {
     matrix: Matrix<i32, 2, 2>,
     matrix_inv: Matrix<i32, 2, 2>,
-    grid: &'a mut Grid<C>,
+    grid: R,
 }
 
+/// A transformed view that can modify the underlying grid
+pub type TransformGrid<'a, C> = Transformed<&'a mut Grid<C>>;
+
+/// A read-only transformed view
+pub type TransformView<'a, C> = Transformed<&'a Grid<C>>;
+
 fn transform_keep_positive_quadrant(
     dimension: Vec2<i32>,
     matrix: Matrix<i32, 2, 2>,
@@ -342,49 +414,81 @@ fn transform_keep_positive_quadrant(
     let dim_tfn = matrix.transform(dimension);
     let abs_dim_tfn = dim_tfn.map(|x| x.abs());
     let offset = ((abs_dim_tfn - dim_tfn) / 2).to_point();
-    let positive_positive_idx = offset + transformed_idx;
-    positive_positive_idx
+    offset + transformed_idx
+}
+
+fn inverse_unimodular(transform: Matrix<i32, 2, 2>) -> Matrix<i32, 2, 2> {
+    let (a, b, c, d) = (
+        transform.elem(0, 0),
+        transform.elem(0, 1),
+        transform.elem(1, 0),
+        transform.elem(1, 1),
+    );
+    let det = a * d - b * c;
+
+    assert!(det == 1 || det == -1, "Transform matrix must be unitary");
+
+    Matrix::from_rows([[d, -b], [-c, a]]) * det
 }
 
-impl<'a, C> TransformGrid<'a, C> {
-    fn transform_point(&self, index: Point) -> Point {
+impl<C, R: Deref<Target = Grid<C>>> Transformed<R> {
+    /// Map a point of the underlying grid into this view
+    pub fn transform_point(&self, index: Point) -> Point {
         transform_keep_positive_quadrant(self.grid.dimension(), self.matrix, index)
     }
 
-    fn inverse_point(&self, index: Point) -> Point {
+    /// Map a point of this view back to the underlying grid
+    pub fn inverse_point(&self, index: Point) -> Point {
         transform_keep_positive_quadrant(self.dimension(), self.matrix_inv, index)
     }
 
-    pub fn from_grid(grid: &'a mut Grid<C>, transform: Matrix<i32, 2, 2>) -> Self {
-        let (a, b, c, d) = (
-            transform.elem(0, 0),
-            transform.elem(0, 1),
-            transform.elem(1, 0),
-            transform.elem(1, 1),
-        );
-        let det = a * d - b * c;
-
-        assert!(det == 1 || det == -1, "Transform matrix must be unitary");
-
-        let inv = Matrix::from_rows([[d, -b], [-c, a]]) * det;
-
-        TransformGrid {
+    pub fn from_grid(grid: R, transform: Matrix<i32, 2, 2>) -> Self {
+        Transformed {
             matrix: transform,
-            matrix_inv: inv,
+            matrix_inv: inverse_unimodular(transform),
             grid,
         }
     }
+
+    /// Apply a further transform on top of the current one
+    pub fn then(self, transform: Matrix<i32, 2, 2>) -> Self {
+        Transformed::from_grid(self.grid, transform * self.matrix)
+    }
+
+    /// Copy the view into a new grid
+    pub fn to_grid(&self) -> Grid<C>
+    where
+        C: Clone,
+    {
+        self.grid.remap(self.dimension(), |p| self.inverse_point(p))
+    }
 }
 
-impl<'a> TransformGrid<'a, ()> {
+impl TransformGrid<'_, ()> {
+    /// Rotate by `count` quarter turns
     pub fn rot(count: usize) -> Matrix<i32, 2, 2> {
         let id = Matrix::identity();
         let rot = Matrix::from_rows([[0, -1], [1, 0]]);
         (0..count).fold(id, |a, _| rot * a)
     }
+
+    /// Mirror left to right
+    pub fn flip_horizontal() -> Matrix<i32, 2, 2> {
+        Matrix::from_rows([[-1, 0], [0, 1]])
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical() -> Matrix<i32, 2, 2> {
+        Matrix::from_rows([[1, 0], [0, -1]])
+    }
+
+    /// Swap rows and columns
+    pub fn transpose() -> Matrix<i32, 2, 2> {
+        Matrix::from_rows([[0, 1], [1, 0]])
+    }
 }
 
-impl<'a, C> Index<Point> for TransformGrid<'a, C> {
+impl<C, R: Deref<Target = Grid<C>>> Index<Point> for Transformed<R> {
     type Output = C;
 
     /// Panics if the point is out of bounds
@@ -393,38 +497,34 @@ impl<'a, C> Index<Point> for TransformGrid<'a, C> {
     }
 }
 
-impl<'a, C> IndexMut<Point> for TransformGrid<'a, C> {
+impl<C, R: DerefMut<Target = Grid<C>>> IndexMut<Point> for Transformed<R> {
     fn index_mut(&mut self, index: Point) -> &mut Self::Output {
-        self.grid.index_mut(self.inverse_point(index))
+        let p = self.inverse_point(index);
+        self.grid.index_mut(p)
     }
 }
 
-impl<'a, C> Index<Point2<usize>> for TransformGrid<'a, C> {
+impl<C, R: Deref<Target = Grid<C>>> Index<Point2<usize>> for Transformed<R> {
     type Output = C;
 
     /// Panics if the point is out of bounds
     fn index(&self, index: Point2<usize>) -> &Self::Output {
-        self.grid.index(
-            self.inverse_point(index.map(|x| x as i32))
-                .map(|x| x as usize),
-        )
+        &self[index.map(|x| x as i32)]
     }
 }
 
-impl<'a, C> IndexMut<Point2<usize>> for TransformGrid<'a, C> {
+impl<C, R: DerefMut<Target = Grid<C>>> IndexMut<Point2<usize>> for Transformed<R> {
     fn index_mut(&mut self, index: Point2<usize>) -> &mut Self::Output {
-        self.grid.index_mut(
-            self.inverse_point(index.map(|x| x as i32))
-                .map(|x| x as usize),
-        )
+        &mut self[index.map(|x| x as i32)]
     }
 }
 
-impl<'a, C> GridTrait for TransformGrid<'a, C> {
+impl<C, R: Deref<Target = Grid<C>>> GridTrait for Transformed<R> {
     type Cell = C;
 
-    fn position(&self, test: fn(&Self::Cell) -> bool) -> Option<Point> {
-        self.grid.position(test).map(|p| self.transform_point(p))
+    /// The first cell passing `test` in this view's row-major order
+    fn position(&self, test: fn(&C) -> bool) -> Option<Point> {
+        self.iter_coordinates().find(|&p| test(&self[p]))
     }
 
     fn contains(&self, coord: Point) -> bool {
@@ -437,29 +537,31 @@ impl<'a, C> GridTrait for TransformGrid<'a, C> {
             .map(|x| x.abs())
     }
 
-    fn adjacent(&self, src: Point) -> ArrayVec<(Point, &Self::Cell), 4> {
-        self.grid.adjacent(self.inverse_point(src))
+    fn adjacent(&self, src: Point) -> ArrayVec<(Point, &C), 4> {
+        UP_RIGHT_DOWN_LEFT
+            .iter()
+            .map(|&d| src + d)
+            .filter(|&n| self.contains(n))
+            .map(|p| (p, &self[p]))
+            .collect()
     }
 
     fn iter_coordinates(&self) -> impl Iterator<Item = Point> {
         PointIterator::new(self.dimension())
     }
 
-    fn get(&self, p: Point) -> Option<&Self::Cell> {
+    fn get(&self, p: Point) -> Option<&C> {
         self.grid.get(self.inverse_point(p))
     }
 
     fn display(&self) -> String
     where
-        Self::Cell: Display,
+        C: Display,
     {
         let mut s = String::new();
-        dbg!(self.dimension(), self.grid.dimension());
         for y in 0..self.dimension().y {
             for x in 0..self.dimension().x {
-                let p = Point::new(x, y);
-                dbg!(p, self.inverse_point(p));
-                s += &format!("{}", self[p]);
+                s += &format!("{}", self[Point::new(x, y)]);
             }
             s += "\n";
         }
@@ -498,6 +600,41 @@ mod tests {
         assert!(!g.contains(p + 8 * v))
     }
 
+    #[test]
+    fn test_owned_transforms() {
+        let g = Grid::read("abc\ndef\n", |x| x);
+        assert_eq!(g.rotate_cw().display(), "da\neb\nfc");
+        assert_eq!(g.rotate_ccw().display(), "cf\nbe\nad");
+        assert_eq!(g.flip_horizontal().display(), "cba\nfed");
+        assert_eq!(g.flip_vertical().display(), "def\nabc");
+        assert_eq!(g.transpose().display(), "ad\nbe\ncf");
+
+        assert_eq!(g.rotate_cw().rotate_ccw(), g);
+        assert_eq!(
+            g.rotate_cw().rotate_cw(),
+            g.flip_horizontal().flip_vertical()
+        );
+        assert_eq!(g.transpose(), g.rotate_cw().flip_horizontal());
+    }
+
+    #[test]
+    fn test_symmetries() {
+        let g = Grid::read("ab\ncd\n", |x| x);
+        let all: Vec<String> = g.symmetries().map(|s| s.display()).collect();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0], "ab\ncd");
+        assert_eq!(all[1], "ca\ndb");
+        assert_eq!(all[4], "ba\ndc");
+        let distinct: std::collections::HashSet<&String> = all.iter().collect();
+        assert_eq!(distinct.len(), 8);
+
+        let symmetric = Grid::read("#.\n.#\n", |x| x);
+        assert_eq!(
+            symmetric.symmetries().filter(|s| *s == symmetric).count(),
+            4
+        );
+    }
+
     #[test]
     fn test_rots() {
         assert_eq!(TransformGrid::<()>::rot(0), Matrix::identity());
@@ -514,7 +651,7 @@ mod tests {
     #[test]
     fn test_tranform_grid() {
         let mut g = Grid::read(
-            &"\
+            "\
 ...........
 ...#...#...
 ....#.#....
@@ -562,4 +699,49 @@ mod tests {
         assert_eq!(tfn.inverse_point(Point::new(0, 4)), Point::new(4, 8));
         assert_eq!(tfn.inverse_point(Point::new(4, 2)), Point::new(2, 4));
     }
+
+    #[test]
+    fn test_transform_view_coordinates() {
+        let g = Grid::read("ab.\n...\n", |x| x);
+        let view = TransformView::from_grid(&g, TransformGrid::rot(1));
+
+        assert_eq!(view.to_grid(), g.rotate_cw());
+        assert_eq!(view.position(|&c| c == 'b'), Some(point2(1, 1)));
+        assert!(view.contains(point2(1, 2)));
+        assert!(!view.contains(point2(2, 0)));
+
+        let mut dots = view.flood_fill(point2(0, 0), Connectivity::Four, |a, b| a == b);
+        dots.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(
+            dots,
+            [point2(0, 0), point2(0, 1), point2(0, 2), point2(1, 2)]
+        );
+
+        let mut adjacent: Vec<(Point, char)> = view
+            .adjacent(point2(1, 0))
+            .into_iter()
+            .map(|(p, &c)| (p, c))
+            .collect();
+        adjacent.sort_by_key(|&(p, _)| (p.y, p.x));
+        assert_eq!(adjacent, [(point2(0, 0), '.'), (point2(1, 1), 'b')]);
+    }
+
+    #[test]
+    fn test_transform_composition() {
+        let mut g = Grid::read("abc\ndef\n", |x| x);
+        let expected = g.rotate_cw().flip_horizontal();
+
+        let mut view = TransformGrid::from_grid(&mut g, TransformGrid::rot(1))
+            .then(TransformGrid::flip_horizontal());
+        assert_eq!(view.to_grid(), expected);
+        assert_eq!(view.position(|&c| c == 'f'), Some(point2(1, 2)));
+
+        view[point2(0, 0)] = 'x';
+        assert_eq!(g.display(), "xbc\ndef");
+
+        let view = TransformView::from_grid(&g, TransformGrid::transpose())
+            .then(TransformGrid::flip_vertical())
+            .then(TransformGrid::flip_horizontal());
+        assert_eq!(view.to_grid(), g.rotate_ccw().rotate_ccw().transpose());
+    }
 }
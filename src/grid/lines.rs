@@ -0,0 +1,159 @@
+use std::{
+    iter::StepBy,
+    slice::{ChunksExact, ChunksExactMut, Iter, IterMut},
+};
+
+use lina::{Vec2, point2, vec2};
+
+use crate::grid::{Grid, Point};
+
+/// The cells visited stepping from a start point in a fixed direction,
+/// ending when the grid is left
+pub struct Ray<'a, C> {
+    grid: &'a Grid<C>,
+    position: Point,
+    direction: Vec2<i32>,
+}
+
+impl<'a, C> Iterator for Ray<'a, C> {
+    type Item = (Point, &'a C);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let p = self.position;
+        let cell = self.grid.get(p)?;
+        self.position += self.direction;
+        Some((p, cell))
+    }
+}
+
+impl<C> Grid<C> {
+    /// The cells of row `y`. Panics if the row is out of bounds
+    pub fn row(&self, y: usize) -> &[C] {
+        &self.inner[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [C] {
+        &mut self.inner[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Every row, top to bottom
+    pub fn rows(&self) -> ChunksExact<'_, C> {
+        self.inner.chunks_exact(self.width.max(1))
+    }
+
+    pub fn rows_mut(&mut self) -> ChunksExactMut<'_, C> {
+        self.inner.chunks_exact_mut(self.width.max(1))
+    }
+
+    /// The cells of column `x`, top to bottom. Panics if the column is out of bounds
+    pub fn column(&self, x: usize) -> StepBy<Iter<'_, C>> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.inner[x..].iter().step_by(self.width)
+    }
+
+    pub fn column_mut(&mut self, x: usize) -> StepBy<IterMut<'_, C>> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.inner[x..].iter_mut().step_by(self.width)
+    }
+
+    /// Every column, left to right
+    pub fn columns(&self) -> impl Iterator<Item = StepBy<Iter<'_, C>>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Walk from `start` by `direction`, including `start`, until leaving the grid.
+    /// Panics if `direction` is zero, as the ray would never end
+    pub fn ray(&self, start: Point, direction: Vec2<i32>) -> Ray<'_, C> {
+        assert!(direction != vec2(0, 0), "ray direction must be non-zero");
+        Ray {
+            grid: self,
+            position: start,
+            direction,
+        }
+    }
+
+    /// Every diagonal running down and to the right, starting from the bottom left corner
+    pub fn diagonals(&self) -> impl Iterator<Item = Ray<'_, C>> {
+        let dim = self.dimension();
+        let left = (1..dim.y).rev().map(|y| point2(0, y));
+        let top = (0..dim.x).map(|x| point2(x, 0));
+        left.chain(top).map(|p| self.ray(p, vec2(1, 1)))
+    }
+
+    /// Every anti-diagonal running down and to the left, starting from the top left corner
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = Ray<'_, C>> {
+        let dim = self.dimension();
+        let top = (0..dim.x).map(|x| point2(x, 0));
+        let right = (1..dim.y).map(move |y| point2(dim.x - 1, y));
+        top.chain(right).map(|p| self.ray(p, vec2(-1, 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::NEIGHBOURS;
+
+    fn cells<'a>(line: impl Iterator<Item = &'a char>) -> String {
+        line.collect()
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let mut g = Grid::read("abc\ndef\n", |x| x);
+        assert_eq!(g.row(1), ['d', 'e', 'f']);
+        assert_eq!(cells(g.column(2)), "cf");
+        assert_eq!(g.rows().count(), 2);
+        assert_eq!(
+            g.columns().map(cells).collect::<Vec<_>>(),
+            ["ad", "be", "cf"]
+        );
+
+        g.row_mut(0).reverse();
+        for c in g.column_mut(1) {
+            *c = '#';
+        }
+        for row in g.rows_mut() {
+            row[0] = row[0].to_ascii_uppercase();
+        }
+        assert_eq!(g.display(), "C#a\nD#f");
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let g = Grid::read("abc\ndef\n", |x| x);
+        let diagonals: Vec<String> = g
+            .diagonals()
+            .map(|d| d.map(|(_, &c)| c).collect())
+            .collect();
+        assert_eq!(diagonals, ["d", "ae", "bf", "c"]);
+
+        let anti: Vec<String> = g
+            .anti_diagonals()
+            .map(|d| d.map(|(_, &c)| c).collect())
+            .collect();
+        assert_eq!(anti, ["a", "bd", "ce", "f"]);
+    }
+
+    #[test]
+    fn test_word_search() {
+        let g = Grid::read("XMAS\nMM..\nA.A.\nS..S\n", |x| x);
+        let found = g
+            .iter_coordinates()
+            .flat_map(|p| NEIGHBOURS.iter().map(move |&d| (p, d)))
+            .filter(|&(p, d)| g.ray(p, d).map(|(_, &c)| c).take(4).eq("XMAS".chars()))
+            .count();
+        assert_eq!(found, 3);
+
+        let ray: Vec<Point> = g.ray(point2(1, 1), vec2(1, 0)).map(|(p, _)| p).collect();
+        assert_eq!(ray, [point2(1, 1), point2(2, 1), point2(3, 1)]);
+        assert_eq!(g.ray(point2(4, 0), vec2(1, 0)).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero")]
+    fn test_zero_ray() {
+        let g = Grid::read("ab\n", |x| x);
+        g.ray(point2(0, 0), vec2(0, 0));
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::{HashSet, VecDeque};
+
+use lina::Vec2;
+
+use crate::{
+    MinMaxIterator,
+    grid::{Grid, GridTrait, NEIGHBOURS, Point, UP_RIGHT_DOWN_LEFT},
+};
+
+/// Which cells count as touching
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Connectivity {
+    /// Orthogonal neighbours, [`UP_RIGHT_DOWN_LEFT`]
+    Four,
+    /// Orthogonal and diagonal neighbours, [`NEIGHBOURS`]
+    Eight,
+}
+
+impl Connectivity {
+    pub fn offsets(self) -> &'static [Vec2<i32>] {
+        match self {
+            Connectivity::Four => &UP_RIGHT_DOWN_LEFT,
+            Connectivity::Eight => &NEIGHBOURS,
+        }
+    }
+}
+
+/// Every cell reachable from `start` through neighbouring cells for which `same` holds,
+/// in breadth first order
+pub(super) fn flood_fill<G: GridTrait + ?Sized>(
+    grid: &G,
+    start: Point,
+    connectivity: Connectivity,
+    same: impl Fn(&G::Cell, &G::Cell) -> bool,
+) -> Vec<Point> {
+    if !grid.contains(start) {
+        return Vec::new();
+    }
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    let mut region = Vec::new();
+    while let Some(p) = queue.pop_front() {
+        region.push(p);
+        for &d in connectivity.offsets() {
+            let q = p + d;
+            if grid.contains(q) && !seen.contains(&q) && same(&grid[p], &grid[q]) {
+                seen.insert(q);
+                queue.push_back(q);
+            }
+        }
+    }
+    region
+}
+
+/// The connected regions of a [`Grid`]
+pub struct Regions {
+    /// The region id of every cell
+    pub labels: Grid<usize>,
+    /// The cells of each region, indexed by id
+    pub cells: Vec<Vec<Point>>,
+}
+
+/// Size and shape of a single region
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RegionMetrics {
+    pub area: usize,
+    /// Number of unit cell edges on the boundary, including around holes
+    pub perimeter: usize,
+    /// Number of straight boundary sides, equal to the number of corners
+    pub sides: usize,
+    /// Inclusive corners of the bounding box
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Regions {
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    fn inside(&self, id: usize, p: Point) -> bool {
+        self.labels.get(p) == Some(&id)
+    }
+
+    pub fn area(&self, id: usize) -> usize {
+        self.cells[id].len()
+    }
+
+    pub fn perimeter(&self, id: usize) -> usize {
+        self.cells[id]
+            .iter()
+            .flat_map(|&p| UP_RIGHT_DOWN_LEFT.iter().map(move |&d| p + d))
+            .filter(|&q| !self.inside(id, q))
+            .count()
+    }
+
+    /// Count corners: at each cell corner, either both orthogonal neighbours are outside
+    /// (convex), or both are inside but the diagonal is not (concave)
+    pub fn sides(&self, id: usize) -> usize {
+        let mut corners = 0;
+        for &p in &self.cells[id] {
+            for i in 0..4 {
+                let (a, b) = (UP_RIGHT_DOWN_LEFT[i], UP_RIGHT_DOWN_LEFT[(i + 1) % 4]);
+                let (a_in, b_in) = (self.inside(id, p + a), self.inside(id, p + b));
+                if (!a_in && !b_in) || (a_in && b_in && !self.inside(id, p + a + b)) {
+                    corners += 1;
+                }
+            }
+        }
+        corners
+    }
+
+    /// The inclusive corners of the smallest box containing the region
+    pub fn bounding_box(&self, id: usize) -> (Point, Point) {
+        let cells = self.cells[id].iter().copied();
+        (cells.clone().min_elementwise(), cells.max_elementwise())
+    }
+
+    pub fn metrics(&self, id: usize) -> RegionMetrics {
+        let (min, max) = self.bounding_box(id);
+        RegionMetrics {
+            area: self.area(id),
+            perimeter: self.perimeter(id),
+            sides: self.sides(id),
+            min,
+            max,
+        }
+    }
+}
+
+impl<C> Grid<C> {
+    /// Label every cell with a region id, joining neighbouring cells for which `same` holds.
+    ///
+    /// Ids are assigned in row-major order of each region's first cell.
+    pub fn label_regions(
+        &self,
+        connectivity: Connectivity,
+        same: impl Fn(&C, &C) -> bool,
+    ) -> Regions {
+        let mut labels = self.map(|_| usize::MAX);
+        let mut cells = Vec::new();
+        for p in self.iter_coordinates() {
+            if labels[p] != usize::MAX {
+                continue;
+            }
+            let region = flood_fill(self, p, connectivity, &same);
+            for &q in &region {
+                labels[q] = cells.len();
+            }
+            cells.push(region);
+        }
+        Regions { labels, cells }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lina::point2;
+
+    use super::*;
+    use crate::grid::sparse::SparseGrid;
+
+    fn garden() -> Grid<char> {
+        Grid::read(
+            "\
+AAAA
+BBCD
+BBCC
+EEEC
+",
+            |x| x,
+        )
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let g = garden();
+        let mut region = g.flood_fill(point2(2, 1), Connectivity::Four, |a, b| a == b);
+        region.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(
+            region,
+            [point2(2, 1), point2(2, 2), point2(3, 2), point2(3, 3)]
+        );
+
+        assert!(
+            g.flood_fill(point2(9, 9), Connectivity::Four, |a, b| a == b)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_labels() {
+        let g = garden();
+        let regions = g.label_regions(Connectivity::Four, |a, b| a == b);
+        assert_eq!(regions.len(), 5);
+        assert_eq!(
+            regions.labels.display(),
+            "\
+0000
+1123
+1122
+4442"
+        );
+        let sizes: Vec<usize> = regions.cells.iter().map(|c| c.len()).collect();
+        assert_eq!(sizes, [4, 4, 4, 1, 3]);
+    }
+
+    #[test]
+    fn test_diagonal_connectivity() {
+        let g = Grid::read("#..\n.#.\n..#\n", |x| x);
+        let four = g.label_regions(Connectivity::Four, |a, b| a == b);
+        let eight = g.label_regions(Connectivity::Eight, |a, b| a == b);
+        assert_eq!(four.len(), 5);
+        assert_eq!(eight.len(), 2);
+        assert_eq!(eight.labels[point2(2, 2)], eight.labels[point2(0, 0)]);
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut g = SparseGrid::new('.');
+        for p in [point2(-3, 0), point2(-2, 0), point2(-2, 1), point2(5, 5)] {
+            g[p] = '#';
+        }
+        let region = g.flood_fill(point2(-3, 0), Connectivity::Eight, |a, b| a == b);
+        assert_eq!(region.len(), 3);
+    }
+
+    fn price(g: &Grid<char>, measure: impl Fn(&RegionMetrics) -> usize) -> usize {
+        let regions = g.label_regions(Connectivity::Four, |a, b| a == b);
+        (0..regions.len())
+            .map(|id| regions.metrics(id))
+            .map(|m| m.area * measure(&m))
+            .sum()
+    }
+
+    #[test]
+    fn test_metrics() {
+        let g = garden();
+        let regions = g.label_regions(Connectivity::Four, |a, b| a == b);
+        let c = regions.labels[point2(2, 1)];
+        assert_eq!(
+            regions.metrics(c),
+            RegionMetrics {
+                area: 4,
+                perimeter: 10,
+                sides: 8,
+                min: point2(2, 1),
+                max: point2(3, 3),
+            }
+        );
+        assert_eq!(price(&g, |m| m.perimeter), 140);
+        assert_eq!(price(&g, |m| m.sides), 80);
+    }
+
+    #[test]
+    fn test_holes() {
+        let g = Grid::read("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO\n", |x| x);
+        let regions = g.label_regions(Connectivity::Four, |a, b| a == b);
+        let o = regions.metrics(0);
+        assert_eq!((o.area, o.perimeter, o.sides), (21, 36, 20));
+        assert_eq!((o.min, o.max), (point2(0, 0), point2(4, 4)));
+        assert_eq!(price(&g, |m| m.perimeter), 772);
+        assert_eq!(price(&g, |m| m.sides), 436);
+    }
+
+    #[test]
+    fn test_diagonal_touching() {
+        let g = Grid::read("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA\n", |x| x);
+        let regions = g.label_regions(Connectivity::Four, |a, b| a == b);
+        assert_eq!(regions.metrics(0).sides, 12);
+        assert_eq!(price(&g, |m| m.sides), 368);
+
+        let joined = g.label_regions(Connectivity::Eight, |a, b| a == b);
+        let b = joined.labels[point2(3, 1)];
+        assert_eq!(joined.labels[point2(2, 3)], b);
+        assert_eq!(joined.metrics(b).sides, 8);
+    }
+}
@@ -30,7 +30,7 @@ impl<C: Clone> Index<Point> for SparseGrid<C> {
 
     /// Panics if the point is out of bounds
     fn index(&self, index: Point) -> &Self::Output {
-        &self.inner.get(&index).unwrap_or(&self.default)
+        self.inner.get(&index).unwrap_or(&self.default)
     }
 }
 
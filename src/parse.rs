@@ -4,7 +4,7 @@ fn nums<I: FromRadix10SignedChecked>(input: &str, numerical: impl Fn(u8) -> bool
     input
         .as_bytes()
         .chunk_by(|&a, &b| numerical(a) == numerical(b))
-        .filter(|x| x.get(0).map(|&x| numerical(x)).unwrap_or(false))
+        .filter(|x| x.first().map(|&x| numerical(x)).unwrap_or(false))
         // .inspect(|x| print!("<{}>", String::from_utf8((*x).to_owned()).unwrap()))
         .filter_map(|x| atoi::atoi::<I>(x))
         .collect()
@@ -32,6 +32,6 @@ Register C: 0
 
 Program: 0,3,5,4,3,0
 ";
-        assert_eq!(nums_positive::<usize>(&s), [117440, 0, 0, 0, 3, 5, 4, 3, 0])
+        assert_eq!(nums_positive::<usize>(s), [117440, 0, 0, 0, 3, 5, 4, 3, 0])
     }
 }
@@ -1,3 +1,7 @@
+use std::{collections::HashMap, hash::Hash};
+
+use num::{Signed, Zero};
+
 type T = usize;
 
 pub struct UnionFind {
@@ -10,31 +14,90 @@ struct Node {
     // parent idx
     parent: usize,
     rank: usize,
+    // number of members, only meaningful for roots
+    size: usize,
+}
+
+impl Node {
+    fn singleton(i: usize) -> Node {
+        Node {
+            parent: i,
+            rank: 0,
+            size: 1,
+        }
+    }
+}
+
+/// Group the elements `0..len` by the root `find` gives them, ordered by smallest member
+fn components(len: usize, mut find: impl FnMut(T) -> T) -> impl Iterator<Item = Vec<T>> {
+    let mut members: HashMap<T, Vec<T>> = HashMap::new();
+    let mut roots = Vec::new();
+    for x in 0..len {
+        let root = find(x);
+        let set = members.entry(root).or_default();
+        if set.is_empty() {
+            roots.push(root);
+        }
+        set.push(x);
+    }
+    roots
+        .into_iter()
+        .map(move |r| members.remove(&r).unwrap_or_default())
+}
+
+/// The sizes of the sets rooted in `nodes`, largest first
+fn sorted_sizes(nodes: &[Node]) -> Vec<usize> {
+    let mut sizes: Vec<usize> = nodes
+        .iter()
+        .enumerate()
+        .filter(|&(x, node)| node.parent == x)
+        .map(|(_, node)| node.size)
+        .collect();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes
 }
 
 impl UnionFind {
     pub fn new(items: usize) -> UnionFind {
         UnionFind {
-            inner: (0..items).map(|i| Node { parent: i, rank: 0 }).collect(),
+            inner: (0..items).map(Node::singleton).collect(),
             distinct_count: items,
         }
     }
 
-    pub fn union(&mut self, a: T, b: T) {
+    /// Add a new singleton set, returning its index
+    pub fn add(&mut self) -> T {
+        let i = self.inner.len();
+        self.inner.push(Node::singleton(i));
+        self.distinct_count += 1;
+        i
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Merge the sets containing `a` and `b`, returning false if they were already one set
+    pub fn union(&mut self, a: T, b: T) -> bool {
         let a_set = self.find(a);
         let b_set = self.find(b);
         if a_set == b_set {
-            return;
+            return false;
         }
         self.link(a_set, b_set);
         self.distinct_count -= 1;
+        true
     }
 
     pub fn find(&mut self, x: T) -> T {
         if x != self.inner[x].parent {
             self.inner[x].parent = self.find(self.inner[x].parent);
         }
-        return self.inner[x].parent;
+        self.inner[x].parent
     }
 
     fn link(&mut self, x_idx: T, y_idx: T) {
@@ -42,11 +105,13 @@ impl UnionFind {
         let mut y = self.inner[y_idx];
         if x.rank > y.rank {
             y.parent = x_idx;
+            x.size += y.size;
         } else {
             if x.rank == y.rank {
                 y.rank += 1;
             }
             x.parent = y_idx;
+            y.size += x.size;
         }
         self.inner[x_idx] = x;
         self.inner[y_idx] = y;
@@ -55,6 +120,448 @@ impl UnionFind {
     pub fn distinct_count(&self) -> usize {
         self.distinct_count
     }
+
+    /// Number of members in the set containing `x`
+    pub fn size_of(&mut self, x: T) -> usize {
+        let root = self.find(x);
+        self.inner[root].size
+    }
+
+    pub fn same(&mut self, a: T, b: T) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The members of each set, each in increasing order, with sets ordered by
+    /// their smallest member
+    pub fn components(&mut self) -> impl Iterator<Item = Vec<T>> {
+        components(self.inner.len(), |x| self.find(x))
+    }
+
+    /// The size of every set, largest first
+    pub fn sorted_sizes(&self) -> Vec<usize> {
+        sorted_sizes(&self.inner)
+    }
+
+    /// Union `edges` in order, yielding each edge that merged two sets.
+    ///
+    /// Stops as soon as everything is connected, without consuming further edges.
+    pub fn merges<W, I>(&mut self, edges: I) -> Merges<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = (T, T, W)>,
+    {
+        Merges {
+            union_find: self,
+            edges: edges.into_iter(),
+            considered: 0,
+            stop_at: 1,
+        }
+    }
+}
+
+/// An edge that joined two previously separate sets
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Merge<W> {
+    pub a: T,
+    pub b: T,
+    pub weight: W,
+    /// Number of edges taken from the input so far, including this one
+    pub considered: usize,
+    /// Number of distinct sets remaining after this merge
+    pub components: usize,
+}
+
+pub struct Merges<'a, I> {
+    union_find: &'a mut UnionFind,
+    edges: I,
+    considered: usize,
+    stop_at: usize,
+}
+
+impl<I> Merges<'_, I> {
+    /// Stop once only `k` sets remain, rather than when everything is connected
+    pub fn until_components(mut self, k: usize) -> Self {
+        self.stop_at = k;
+        self
+    }
+}
+
+impl<W, I: Iterator<Item = (T, T, W)>> Iterator for Merges<'_, I> {
+    type Item = Merge<W>;
+
+    fn next(&mut self) -> Option<Merge<W>> {
+        while self.union_find.distinct_count() > self.stop_at {
+            let (a, b, weight) = self.edges.next()?;
+            self.considered += 1;
+            if self.union_find.union(a, b) {
+                return Some(Merge {
+                    a,
+                    b,
+                    weight,
+                    considered: self.considered,
+                    components: self.union_find.distinct_count(),
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Kruskal's algorithm over nodes `0..nodes`, returning the chosen edges and their
+/// total weight. A disconnected graph gives a minimum spanning forest.
+pub fn minimum_spanning_tree<W: Ord + Zero + Copy>(
+    nodes: usize,
+    edges: impl IntoIterator<Item = (T, T, W)>,
+) -> (Vec<(T, T, W)>, W) {
+    let mut edges: Vec<(T, T, W)> = edges.into_iter().collect();
+    edges.sort_by_key(|&(_, _, w)| w);
+
+    let mut u = UnionFind::new(nodes);
+    let chosen: Vec<(T, T, W)> = u.merges(edges).map(|m| (m.a, m.b, m.weight)).collect();
+    let total = chosen.iter().fold(W::zero(), |acc, &(_, _, w)| acc + w);
+    (chosen, total)
+}
+
+/// A [`UnionFind`] whose unions can be undone.
+///
+/// Uses union by rank without path compression, so `find` is O(log n) and every
+/// union changes a single root which can be restored exactly.
+pub struct RollbackUnionFind {
+    inner: Vec<Node>,
+    distinct_count: usize,
+    history: Vec<Change>,
+}
+
+enum Change {
+    Add,
+    Link {
+        child: T,
+        parent: T,
+        rank_increased: bool,
+    },
+}
+
+/// A point in the history of a [`RollbackUnionFind`] that can be returned to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Snapshot(usize);
+
+impl RollbackUnionFind {
+    pub fn new(items: usize) -> RollbackUnionFind {
+        RollbackUnionFind {
+            inner: (0..items).map(Node::singleton).collect(),
+            distinct_count: items,
+            history: Vec::new(),
+        }
+    }
+
+    /// Add a new singleton set, returning its index
+    pub fn add(&mut self) -> T {
+        let i = self.inner.len();
+        self.inner.push(Node::singleton(i));
+        self.distinct_count += 1;
+        self.history.push(Change::Add);
+        i
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Merge the sets containing `a` and `b`, returning false if they were already one set
+    pub fn union(&mut self, a: T, b: T) -> bool {
+        let a_set = self.find(a);
+        let b_set = self.find(b);
+        if a_set == b_set {
+            return false;
+        }
+        let (child, parent) = if self.inner[a_set].rank > self.inner[b_set].rank {
+            (b_set, a_set)
+        } else {
+            (a_set, b_set)
+        };
+        let rank_increased = self.inner[child].rank == self.inner[parent].rank;
+        if rank_increased {
+            self.inner[parent].rank += 1;
+        }
+        self.inner[child].parent = parent;
+        self.inner[parent].size += self.inner[child].size;
+        self.distinct_count -= 1;
+        self.history.push(Change::Link {
+            child,
+            parent,
+            rank_increased,
+        });
+        true
+    }
+
+    pub fn find(&self, mut x: T) -> T {
+        while x != self.inner[x].parent {
+            x = self.inner[x].parent;
+        }
+        x
+    }
+
+    pub fn distinct_count(&self) -> usize {
+        self.distinct_count
+    }
+
+    /// Number of members in the set containing `x`
+    pub fn size_of(&self, x: T) -> usize {
+        self.inner[self.find(x)].size
+    }
+
+    pub fn same(&self, a: T, b: T) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The members of each set, each in increasing order, with sets ordered by
+    /// their smallest member
+    pub fn components(&self) -> impl Iterator<Item = Vec<T>> {
+        components(self.inner.len(), |x| self.find(x))
+    }
+
+    /// The size of every set, largest first
+    pub fn sorted_sizes(&self) -> Vec<usize> {
+        sorted_sizes(&self.inner)
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot(self.history.len())
+    }
+
+    /// Undo every `union` and `add` made since `to` was taken
+    pub fn rollback(&mut self, to: Snapshot) {
+        let undone = self.history.split_off(to.0);
+        for change in undone.into_iter().rev() {
+            match change {
+                Change::Add => {
+                    self.inner.pop();
+                    self.distinct_count -= 1;
+                }
+                Change::Link {
+                    child,
+                    parent,
+                    rank_increased,
+                } => {
+                    self.inner[child].parent = child;
+                    self.inner[parent].size -= self.inner[child].size;
+                    if rank_increased {
+                        self.inner[parent].rank -= 1;
+                    }
+                    self.distinct_count += 1;
+                }
+            }
+        }
+    }
+}
+
+/// A union-find storing each element's offset from its root, for constraints of the
+/// form "a is `delta` more than b". Offsets can be negative, so `W` must be signed.
+pub struct WeightedUnionFind<W> {
+    inner: Vec<Node>,
+    // value of an element minus the value of its parent
+    offset: Vec<W>,
+    distinct_count: usize,
+}
+
+/// A relation that disagrees with the difference already implied by earlier unions
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Contradiction<W> {
+    pub implied: W,
+    pub given: W,
+}
+
+impl<W: Copy + Signed> WeightedUnionFind<W> {
+    pub fn new(items: usize) -> WeightedUnionFind<W> {
+        WeightedUnionFind {
+            inner: (0..items).map(Node::singleton).collect(),
+            offset: vec![W::zero(); items],
+            distinct_count: items,
+        }
+    }
+
+    /// The root of `x` and the value of `x` relative to it
+    pub fn find(&mut self, x: T) -> (T, W) {
+        let parent = self.inner[x].parent;
+        if x != parent {
+            let (root, parent_offset) = self.find(parent);
+            self.inner[x].parent = root;
+            self.offset[x] = self.offset[x] + parent_offset;
+        }
+        (self.inner[x].parent, self.offset[x])
+    }
+
+    /// Record that `a - b == delta`
+    pub fn union(&mut self, a: T, b: T, delta: W) -> Result<(), Contradiction<W>> {
+        let (a_root, a_offset) = self.find(a);
+        let (b_root, b_offset) = self.find(b);
+        if a_root == b_root {
+            let implied = a_offset - b_offset;
+            return if implied == delta {
+                Ok(())
+            } else {
+                Err(Contradiction {
+                    implied,
+                    given: delta,
+                })
+            };
+        }
+        // a_root - b_root
+        let roots_delta = delta - a_offset + b_offset;
+        let (x, y) = (self.inner[a_root], self.inner[b_root]);
+        if x.rank > y.rank {
+            self.inner[b_root].parent = a_root;
+            self.inner[a_root].size += y.size;
+            self.offset[b_root] = -roots_delta;
+        } else {
+            if x.rank == y.rank {
+                self.inner[b_root].rank += 1;
+            }
+            self.inner[a_root].parent = b_root;
+            self.inner[b_root].size += x.size;
+            self.offset[a_root] = roots_delta;
+        }
+        self.distinct_count -= 1;
+        Ok(())
+    }
+
+    /// `a - b`, if the two are related
+    pub fn diff(&mut self, a: T, b: T) -> Option<W> {
+        let (a_root, a_offset) = self.find(a);
+        let (b_root, b_offset) = self.find(b);
+        (a_root == b_root).then(|| a_offset - b_offset)
+    }
+
+    pub fn same(&mut self, a: T, b: T) -> bool {
+        self.find(a).0 == self.find(b).0
+    }
+
+    pub fn distinct_count(&self) -> usize {
+        self.distinct_count
+    }
+}
+
+/// A [`UnionFind`] over arbitrary hashable keys.
+///
+/// Keys are added as singletons the first time they are seen, and are mapped to
+/// dense indices into the underlying index-based [`UnionFind`].
+pub struct KeyedUnionFind<K> {
+    core: UnionFind,
+    indices: HashMap<K, T>,
+    keys: Vec<K>,
+}
+
+impl<K: Hash + Eq + Clone> Default for KeyedUnionFind<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedUnionFind<K> {
+    pub fn new() -> KeyedUnionFind<K> {
+        KeyedUnionFind {
+            core: UnionFind::new(0),
+            indices: HashMap::new(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// Start with every key in `keys` as its own set
+    pub fn from_keys(keys: impl IntoIterator<Item = K>) -> KeyedUnionFind<K> {
+        let mut u = KeyedUnionFind::new();
+        for k in keys {
+            u.index_or_insert(k);
+        }
+        u
+    }
+
+    /// The dense index of `key`, adding it as a singleton if it is new
+    pub fn index_or_insert(&mut self, key: K) -> T {
+        if let Some(&i) = self.indices.get(&key) {
+            return i;
+        }
+        let i = self.core.add();
+        self.indices.insert(key.clone(), i);
+        self.keys.push(key);
+        i
+    }
+
+    pub fn index(&self, key: &K) -> Option<T> {
+        self.indices.get(key).copied()
+    }
+
+    pub fn key(&self, index: T) -> &K {
+        &self.keys[index]
+    }
+
+    /// Merge the sets containing `a` and `b`, returning false if they were already one set
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let a = self.index_or_insert(a);
+        let b = self.index_or_insert(b);
+        self.core.union(a, b)
+    }
+
+    /// The representative key of the set containing `x`
+    pub fn find(&mut self, x: K) -> K {
+        let i = self.index_or_insert(x);
+        let root = self.core.find(i);
+        self.keys[root].clone()
+    }
+
+    /// Number of distinct sets among the keys seen so far
+    pub fn distinct_count(&self) -> usize {
+        self.core.distinct_count()
+    }
+
+    pub fn size_of(&mut self, x: K) -> usize {
+        let i = self.index_or_insert(x);
+        self.core.size_of(i)
+    }
+
+    pub fn same(&mut self, a: K, b: K) -> bool {
+        let a = self.index_or_insert(a);
+        let b = self.index_or_insert(b);
+        self.core.same(a, b)
+    }
+
+    /// The members of each set, in order of first appearance
+    pub fn components(&mut self) -> impl Iterator<Item = Vec<K>> {
+        let keys = &self.keys;
+        self.core
+            .components()
+            .map(|set| set.into_iter().map(|i| keys[i].clone()).collect())
+    }
+
+    /// The size of every set, largest first
+    pub fn sorted_sizes(&self) -> Vec<usize> {
+        self.core.sorted_sizes()
+    }
+
+    /// Number of keys seen so far
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// [`union`](Self::union) by index, skipping the key lookup
+    pub fn union_index(&mut self, a: T, b: T) -> bool {
+        self.core.union(a, b)
+    }
+
+    /// The index of the root of the set containing index `x`
+    pub fn find_index(&mut self, x: T) -> T {
+        self.core.find(x)
+    }
+
+    pub fn same_index(&mut self, a: T, b: T) -> bool {
+        self.core.same(a, b)
+    }
 }
 
 #[cfg(test)]
@@ -109,4 +616,196 @@ mod tests {
         assert_ne!(u.find(7), u.find(0));
         assert_ne!(u.find(8), u.find(0));
     }
+
+    #[test]
+    fn test_keyed() {
+        let mut u = KeyedUnionFind::new();
+        u.union((0, 0), (0, 1));
+        u.union((5, 5), (5, 6));
+        assert_eq!(u.len(), 4);
+        assert_eq!(u.distinct_count(), 2);
+
+        assert_eq!(u.find((0, 0)), u.find((0, 1)));
+        assert_ne!(u.find((0, 0)), u.find((5, 5)));
+
+        assert_eq!(u.find((9, 9)), (9, 9));
+        assert_eq!(u.distinct_count(), 3);
+
+        let a = u.index(&(0, 0)).unwrap();
+        let b = u.index(&(5, 6)).unwrap();
+        assert!(u.union_index(a, b));
+        assert!(u.same_index(a, b));
+        let root = u.find_index(b);
+        let root_key = u.find((0, 0));
+        assert_eq!(u.index(&root_key), Some(root));
+        assert_eq!(u.distinct_count(), 2);
+        assert_eq!(u.find((0, 1)), u.find((5, 5)));
+        assert_eq!(u.key(a), &(0, 0));
+    }
+
+    #[test]
+    fn test_keyed_from_keys() {
+        let mut u = KeyedUnionFind::from_keys(["a", "b", "c"]);
+        assert_eq!(u.distinct_count(), 3);
+        u.union("a", "c");
+        assert_eq!(u.distinct_count(), 2);
+        assert_eq!(u.index(&"d"), None);
+    }
+
+    #[test]
+    fn test_sizes_and_components() {
+        let mut u = UnionFind::new(8);
+        u.union(0, 3);
+        u.union(3, 6);
+        u.union(1, 7);
+        u.union(6, 0);
+
+        assert_eq!(u.size_of(6), 3);
+        assert_eq!(u.size_of(7), 2);
+        assert_eq!(u.size_of(2), 1);
+        assert!(u.same(0, 6));
+        assert!(!u.same(0, 1));
+
+        assert_eq!(u.sorted_sizes(), [3, 2, 1, 1, 1]);
+        assert_eq!(u.sorted_sizes()[..3].iter().product::<usize>(), 6);
+
+        let components: Vec<Vec<usize>> = u.components().collect();
+        assert_eq!(
+            components,
+            [vec![0, 3, 6], vec![1, 7], vec![2], vec![4], vec![5]]
+        );
+    }
+
+    #[test]
+    fn test_keyed_components() {
+        let mut u = KeyedUnionFind::new();
+        u.union("x", "y");
+        u.union("z", "y");
+        u.union("p", "q");
+        assert_eq!(u.size_of("x"), 3);
+        assert!(u.same("p", "q"));
+        assert_eq!(u.sorted_sizes(), [3, 2]);
+        let components: Vec<Vec<&str>> = u.components().collect();
+        assert_eq!(components, [vec!["x", "y", "z"], vec!["p", "q"]]);
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut u = RollbackUnionFind::new(6);
+        u.union(0, 1);
+        u.union(2, 3);
+        let start = u.snapshot();
+
+        u.union(1, 2);
+        u.union(4, 5);
+        assert!(u.same(0, 3));
+        assert_eq!(u.size_of(3), 4);
+        assert_eq!(u.distinct_count(), 2);
+
+        let middle = u.snapshot();
+        let extra = u.add();
+        u.union(extra, 5);
+        assert_eq!(u.sorted_sizes(), [4, 3]);
+
+        u.rollback(middle);
+        assert_eq!(u.len(), 6);
+        assert_eq!(u.distinct_count(), 2);
+        assert_eq!(u.size_of(4), 2);
+
+        u.rollback(start);
+        assert!(!u.same(0, 3));
+        assert!(u.same(2, 3));
+        assert_eq!(u.distinct_count(), 4);
+        let components: Vec<Vec<usize>> = u.components().collect();
+        assert_eq!(components, [vec![0, 1], vec![2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn test_weighted() {
+        let mut u = WeightedUnionFind::<i64>::new(6);
+        assert_eq!(u.union(0, 1, 3), Ok(()));
+        assert_eq!(u.union(2, 1, -2), Ok(()));
+        assert_eq!(u.union(3, 4, 10), Ok(()));
+
+        assert_eq!(u.diff(0, 2), Some(5));
+        assert_eq!(u.diff(2, 0), Some(-5));
+        assert_eq!(u.diff(0, 3), None);
+
+        assert_eq!(u.union(4, 2, 1), Ok(()));
+        assert_eq!(u.diff(3, 0), Some(6));
+        assert_eq!(u.distinct_count(), 2);
+
+        assert_eq!(u.union(0, 2, 5), Ok(()));
+        assert_eq!(
+            u.union(0, 3, 1),
+            Err(Contradiction {
+                implied: -6,
+                given: 1
+            })
+        );
+        assert!(u.same(0, 4));
+        assert!(!u.same(0, 5));
+        assert_eq!(u.distinct_count(), 2);
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let edges = [
+            (0, 1, 4),
+            (0, 7, 8),
+            (1, 2, 8),
+            (1, 7, 11),
+            (2, 3, 7),
+            (2, 8, 2),
+            (2, 5, 4),
+            (3, 4, 9),
+            (3, 5, 14),
+            (4, 5, 10),
+            (5, 6, 2),
+            (6, 7, 1),
+            (6, 8, 6),
+            (7, 8, 7),
+        ];
+        let (chosen, total) = minimum_spanning_tree(9, edges);
+        assert_eq!(chosen.len(), 8);
+        assert_eq!(total, 37);
+
+        let (forest, total) = minimum_spanning_tree(4, [(0, 1, 5), (2, 3, 1)]);
+        assert_eq!(forest, [(2, 3, 1), (0, 1, 5)]);
+        assert_eq!(total, 6);
+    }
+
+    #[test]
+    fn test_merges() {
+        let edges = [
+            (0, 1, 'a'),
+            (1, 2, 'b'),
+            (0, 2, 'c'),
+            (3, 4, 'd'),
+            (2, 3, 'e'),
+            (4, 5, 'f'),
+        ];
+
+        let mut u = UnionFind::new(6);
+        let merged: Vec<char> = u
+            .merges(edges)
+            .until_components(3)
+            .map(|m| m.weight)
+            .collect();
+        assert_eq!(merged, ['a', 'b', 'd']);
+        assert_eq!(u.sorted_sizes(), [3, 2, 1]);
+
+        let mut u = UnionFind::new(6);
+        let last = u.merges(edges).last().unwrap();
+        assert_eq!(
+            last,
+            Merge {
+                a: 4,
+                b: 5,
+                weight: 'f',
+                considered: 6,
+                components: 1
+            }
+        );
+    }
 }